@@ -0,0 +1,276 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How long a downloaded tarball is used before the remote is checked for changes
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Controls when cached remote starters are revalidated
+#[derive(Debug, Clone, Copy)]
+pub struct CachePolicy {
+    /// How long a cached download is used without asking the remote
+    pub ttl: Duration,
+    /// Always ask the remote, ignoring `ttl`
+    pub refresh: bool,
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        Self {
+            ttl: DEFAULT_CACHE_TTL,
+            refresh: false,
+        }
+    }
+}
+
+/// Information about a cached download, stored as JSON next to the downloaded file
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CacheMetadata {
+    pub url: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    /// Unix timestamp (seconds) of the last download or successful revalidation
+    pub fetched_at: u64,
+}
+
+impl CacheMetadata {
+    /// Path of the metadata file for the cached file at `file_path`
+    pub fn path_for(file_path: &Path) -> PathBuf {
+        let mut name = file_path.file_name().unwrap_or_default().to_os_string();
+        name.push(".json");
+        file_path.with_file_name(name)
+    }
+
+    /// Load the metadata for the cached file at `file_path`, if any
+    pub fn load(file_path: &Path) -> Option<Self> {
+        let contents = fs::read_to_string(Self::path_for(file_path)).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    pub fn save(&self, file_path: &Path) -> Result<()> {
+        fs::write(
+            Self::path_for(file_path),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        Ok(())
+    }
+
+    /// Time since the file was last downloaded or revalidated
+    pub fn age(&self) -> Duration {
+        Duration::from_secs(now_secs().saturating_sub(self.fetched_at))
+    }
+
+    /// Whether the cached file can be used without asking the remote
+    pub fn is_fresh(&self, policy: &CachePolicy) -> bool {
+        !policy.refresh && self.age() < policy.ttl
+    }
+}
+
+/// Current time as a unix timestamp in seconds
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// A cached remote repository, stored at `<cache_dir>/<host>/<owner>/<repo>`
+#[derive(Debug, Serialize, Clone)]
+pub struct CacheEntry {
+    /// Identifies the repository, e.g. "github/kevinschaul/jump-start"
+    pub source: String,
+    pub path: PathBuf,
+    /// Total size on disk in bytes
+    pub size: u64,
+    /// Unix timestamp (seconds) of the last download or update
+    pub updated_at: u64,
+    /// URL the tarball was downloaded from, if the repository was downloaded in tar mode
+    pub url: Option<String>,
+}
+
+impl CacheEntry {
+    pub fn age(&self) -> Duration {
+        Duration::from_secs(now_secs().saturating_sub(self.updated_at))
+    }
+}
+
+/// List the repositories in the cache, sorted by source
+pub fn list_cache_entries(cache_dir: &Path) -> Result<Vec<CacheEntry>> {
+    let mut entries = Vec::new();
+    if !cache_dir.is_dir() {
+        return Ok(entries);
+    }
+
+    for host in read_subdirs(cache_dir)? {
+        for owner in read_subdirs(&host)? {
+            for repo in read_subdirs(&owner)? {
+                entries.push(read_cache_entry(cache_dir, &repo)?);
+            }
+        }
+    }
+
+    entries.sort_by(|a, b| a.source.cmp(&b.source));
+    Ok(entries)
+}
+
+fn read_subdirs(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut dirs = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            dirs.push(entry.path());
+        }
+    }
+    Ok(dirs)
+}
+
+fn read_cache_entry(cache_dir: &Path, repo_dir: &Path) -> Result<CacheEntry> {
+    let source = repo_dir
+        .strip_prefix(cache_dir)
+        .unwrap_or(repo_dir)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+
+    let metadata = CacheMetadata::load(&repo_dir.join("HEAD.tar.gz"));
+
+    let updated_at = match &metadata {
+        Some(metadata) => metadata.fetched_at,
+        // Git clones record their last update in FETCH_HEAD, or HEAD if never updated
+        None => [
+            modified_secs(repo_dir),
+            modified_secs(&repo_dir.join(".git-clone/.git/FETCH_HEAD")),
+            modified_secs(&repo_dir.join(".git-clone/.git/HEAD")),
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+        .unwrap_or_default(),
+    };
+
+    Ok(CacheEntry {
+        source,
+        path: repo_dir.to_path_buf(),
+        size: dir_size(repo_dir)?,
+        updated_at,
+        url: metadata.map(|m| m.url),
+    })
+}
+
+fn modified_secs(path: &Path) -> Option<u64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_secs())
+}
+
+/// Total size of the files in `dir`, without following symlinks
+fn dir_size(dir: &Path) -> Result<u64> {
+    let mut size = 0;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            size += dir_size(&entry.path())?;
+        } else if file_type.is_file() {
+            size += entry.metadata()?.len();
+        }
+    }
+    Ok(size)
+}
+
+/// Remove a cached repository, along with its owner and host directories if they end up empty
+pub fn remove_cache_entry(cache_dir: &Path, entry: &CacheEntry) -> Result<()> {
+    fs::remove_dir_all(&entry.path)?;
+
+    let mut dir = entry.path.parent();
+    while let Some(parent) = dir {
+        if parent == cache_dir || fs::remove_dir(parent).is_err() {
+            break;
+        }
+        dir = parent.parent();
+    }
+    Ok(())
+}
+
+/// Parse a duration such as "30d", "12h", "45m" or "90s". Plain numbers are seconds.
+pub fn parse_duration(s: &str) -> Result<Duration> {
+    let s = s.trim();
+    let (number, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => s.split_at(i),
+        None => (s, "s"),
+    };
+    let number: u64 = number
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid duration {:?}, expected e.g. 30d or 12h", s))?;
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => anyhow::bail!(
+            "Invalid duration unit {:?}, expected one of s, m, h, d, w",
+            unit
+        ),
+    };
+    let secs = number
+        .checked_mul(multiplier)
+        .ok_or_else(|| anyhow::anyhow!("Duration {:?} is too long", s))?;
+    Ok(Duration::from_secs(secs))
+}
+
+/// Format a duration for humans, using its largest unit, e.g. "3d"
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m", secs / 60),
+        3600..86400 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
+/// Format a size in bytes for humans, e.g. "1.2 MB"
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[unit])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() -> Result<()> {
+        assert_eq!(parse_duration("90")?, Duration::from_secs(90));
+        assert_eq!(parse_duration("45m")?, Duration::from_secs(45 * 60));
+        assert_eq!(parse_duration("12h")?, Duration::from_secs(12 * 3600));
+        assert_eq!(parse_duration("30d")?, Duration::from_secs(30 * 86400));
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("3y").is_err());
+        assert!(parse_duration("99999999999999999w").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_duration_and_size() {
+        assert_eq!(format_duration(Duration::from_secs(5)), "5s");
+        assert_eq!(format_duration(Duration::from_secs(7200)), "2h");
+        assert_eq!(format_duration(Duration::from_secs(3 * 86400 + 5)), "3d");
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MB");
+    }
+}
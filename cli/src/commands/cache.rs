@@ -0,0 +1,123 @@
+use crate::Config;
+use crate::cache::{
+    CacheEntry, format_duration, format_size, list_cache_entries, remove_cache_entry,
+};
+use crate::config::get_cache_dir;
+use anyhow::Result;
+use log::{debug, info};
+use serde_json::json;
+use std::time::Duration;
+
+/// Print the path to the cache directory
+pub fn path(config: Config) -> Result<()> {
+    println!("{}", get_cache_dir(&config).display());
+    Ok(())
+}
+
+/// Print the cached repositories with their size, age and source
+pub fn list(config: Config, json: bool) -> Result<()> {
+    let cache_dir = get_cache_dir(&config);
+    debug!("Listing cache at {:?}", cache_dir);
+    let entries = list_cache_entries(&cache_dir)?;
+
+    for entry in &entries {
+        println!("{}", format_entry(entry, json)?);
+    }
+
+    if !json {
+        let total: u64 = entries.iter().map(|e| e.size).sum();
+        info!(
+            "{} cached repositories, {} total",
+            entries.len(),
+            format_size(total)
+        );
+    }
+
+    Ok(())
+}
+
+/// Formats a cache entry as either a JSON string or a human-readable line
+pub fn format_entry(entry: &CacheEntry, json: bool) -> Result<String> {
+    if json {
+        let result_json = json!({
+            "source": entry.source,
+            "path": entry.path,
+            "size": entry.size,
+            "updated_at": entry.updated_at,
+            "age_secs": entry.age().as_secs(),
+            "url": entry.url,
+        });
+        Ok(serde_json::to_string(&result_json)?)
+    } else {
+        Ok(format!(
+            "{:<40} {:>10} {:>6} ago  {}",
+            entry.source,
+            format_size(entry.size),
+            format_duration(entry.age()),
+            entry.url.as_deref().unwrap_or("")
+        ))
+    }
+}
+
+/// Whether `selector` refers to `entry`, either by its full source ("github/user/repo") or by
+/// its repository ("user/repo" or "@user/repo"). Copies pinned to a ref, cached as
+/// "repo@ref", are selected by their repository or by "repo@ref"
+fn matches_entry(entry: &CacheEntry, selector: &str) -> bool {
+    let selector = selector.trim_start_matches('@').trim_end_matches('/');
+    let unpinned = match entry.source.split_once('@') {
+        Some((unpinned, _)) => unpinned,
+        None => &entry.source,
+    };
+    [entry.source.as_str(), unpinned]
+        .iter()
+        .any(|source| *source == selector || source.ends_with(&format!("/{}", selector)))
+}
+
+/// Remove the selected cached repositories, or everything if `selectors` is empty
+pub fn clear(config: Config, selectors: &[String]) -> Result<()> {
+    let cache_dir = get_cache_dir(&config);
+    let entries = list_cache_entries(&cache_dir)?;
+
+    let selected: Vec<&CacheEntry> = if selectors.is_empty() {
+        entries.iter().collect()
+    } else {
+        for selector in selectors {
+            if !entries.iter().any(|e| matches_entry(e, selector)) {
+                anyhow::bail!(
+                    "No cached repository matches {:?}. Run `jump-start cache list` to see cached repositories",
+                    selector
+                );
+            }
+        }
+        entries
+            .iter()
+            .filter(|e| selectors.iter().any(|s| matches_entry(e, s)))
+            .collect()
+    };
+
+    remove_entries(&cache_dir, &selected)
+}
+
+/// Remove cached repositories that haven't been updated within `older_than`
+pub fn prune(config: Config, older_than: Duration) -> Result<()> {
+    let cache_dir = get_cache_dir(&config);
+    let entries = list_cache_entries(&cache_dir)?;
+
+    let selected: Vec<&CacheEntry> = entries.iter().filter(|e| e.age() > older_than).collect();
+    remove_entries(&cache_dir, &selected)
+}
+
+fn remove_entries(cache_dir: &std::path::Path, entries: &[&CacheEntry]) -> Result<()> {
+    let mut freed = 0;
+    for entry in entries {
+        remove_cache_entry(cache_dir, entry)?;
+        info!("Removed {} ({})", entry.source, format_size(entry.size));
+        freed += entry.size;
+    }
+    info!(
+        "Removed {} cached repositories, freed {}",
+        entries.len(),
+        format_size(freed)
+    );
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use crate::config::get_config_path;
 use anyhow::Result;
-use log::{debug, info};
+use log::debug;
 
 pub fn config() -> Result<()> {
     let config_path = get_config_path();
@@ -13,7 +13,7 @@ pub fn config() -> Result<()> {
     );
 
     // This is the standard output that always shows
-    info!("{}", config_path.display());
+    println!("{}", config_path.display());
 
     Ok(())
 }
@@ -0,0 +1,256 @@
+use crate::Config;
+use crate::commands::r#use::{UseOptions, fetch_starter, plan_starter};
+use crate::copy::PlannedFile;
+use crate::manifest::Manifest;
+use anyhow::Result;
+use diffy::{DiffOptions, Line, PatchFormatter};
+use log::{debug, info};
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+
+/// Widest `--stat` bar, in characters
+const MAX_STAT_WIDTH: usize = 40;
+
+/// How a project file differs from the starter's version of it
+#[derive(Debug, PartialEq)]
+pub struct FileDiff {
+    /// Path relative to the project directory
+    pub path: String,
+    /// Unified diff from the starter's version to the project's. `None` for binary files
+    pub patch: Option<String>,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+/// Compare the starter's `files` with the project in `dir`.
+///
+/// Files missing from the project show up as deleted. Files that only exist in the project are
+/// ignored, since projects are expected to grow beyond their starter.
+pub fn diff_files(files: &[PlannedFile], dir: &Path, color: bool) -> Result<Vec<FileDiff>> {
+    let formatter = if color {
+        PatchFormatter::new().with_color()
+    } else {
+        PatchFormatter::new()
+    };
+
+    let mut diffs = Vec::new();
+    for file in files {
+        let path = file.key();
+        let project_path = dir.join(&file.path);
+        let starter_contents = file.contents()?;
+        let project_contents = if project_path.is_file() {
+            Some(fs::read(&project_path)?)
+        } else {
+            None
+        };
+        if project_contents.as_ref() == Some(&starter_contents) {
+            debug!("{} matches the starter", path);
+            continue;
+        }
+
+        let (Ok(original), Ok(modified)) = (
+            String::from_utf8(starter_contents),
+            String::from_utf8(project_contents.clone().unwrap_or_default()),
+        ) else {
+            diffs.push(FileDiff {
+                path,
+                patch: None,
+                insertions: 0,
+                deletions: 0,
+            });
+            continue;
+        };
+
+        let modified_name = match project_contents {
+            Some(_) => format!("b/{}", path),
+            None => "/dev/null".to_string(),
+        };
+        let patch = DiffOptions::new()
+            .set_original_filename(format!("a/{}", path))
+            .set_modified_filename(modified_name)
+            .create_patch(&original, &modified);
+
+        let lines = patch.hunks().iter().flat_map(|h| h.lines());
+        let insertions = lines
+            .clone()
+            .filter(|l| matches!(l, Line::Insert(_)))
+            .count();
+        let deletions = lines.filter(|l| matches!(l, Line::Delete(_))).count();
+
+        diffs.push(FileDiff {
+            patch: Some(formatter.fmt_patch(&patch).to_string()),
+            path,
+            insertions,
+            deletions,
+        });
+    }
+    Ok(diffs)
+}
+
+/// Summarise `diffs` like `git diff --stat`
+///
+/// ```
+/// use jump_start::commands::diff::{FileDiff, format_stat};
+///
+/// let diffs = vec![FileDiff {
+///     path: "src/index.js".to_string(),
+///     patch: Some(String::new()),
+///     insertions: 2,
+///     deletions: 1,
+/// }];
+/// assert_eq!(
+///     format_stat(&diffs),
+///     " src/index.js | 3 ++-\n 1 files changed, 2 insertions(+), 1 deletions(-)"
+/// );
+/// ```
+pub fn format_stat(diffs: &[FileDiff]) -> String {
+    let path_width = diffs.iter().map(|d| d.path.len()).max().unwrap_or(0);
+    let max_changes = diffs
+        .iter()
+        .map(|d| d.insertions + d.deletions)
+        .max()
+        .unwrap_or(0);
+    let count_width = max_changes.to_string().len();
+
+    let mut lines = Vec::new();
+    for diff in diffs {
+        let line = match diff.patch {
+            None => format!(" {:<path_width$} | Bin", diff.path),
+            Some(_) => {
+                let changes = diff.insertions + diff.deletions;
+                // Scale bars down so the largest fits, keeping every change visible
+                let scale = |n: usize| {
+                    if max_changes <= MAX_STAT_WIDTH || n == 0 {
+                        n
+                    } else {
+                        (n * MAX_STAT_WIDTH / max_changes).max(1)
+                    }
+                };
+                format!(
+                    " {:<path_width$} | {:>count_width$} {}{}",
+                    diff.path,
+                    changes,
+                    "+".repeat(scale(diff.insertions)),
+                    "-".repeat(scale(diff.deletions)),
+                )
+            }
+        };
+        lines.push(line);
+    }
+
+    lines.push(format!(
+        " {} files changed, {} insertions(+), {} deletions(-)",
+        diffs.len(),
+        diffs.iter().map(|d| d.insertions).sum::<usize>(),
+        diffs.iter().map(|d| d.deletions).sum::<usize>()
+    ));
+    lines.join("\n")
+}
+
+/// Show how the project in `dir` has diverged from its starter.
+///
+/// Without `starter_identifier`, the starter is read from the project's manifest, which `use`
+/// writes unless passed `--no-manifest`. Template variables recorded there are used to render the
+/// starter, so that rendered values don't show up as differences.
+pub fn diff(
+    config: Config,
+    starter_identifier: Option<&str>,
+    dir: Option<&str>,
+    stat: bool,
+    options: &UseOptions,
+) -> Result<()> {
+    let dir = PathBuf::from(dir.unwrap_or("."));
+    let manifest = Manifest::load(&dir)?;
+
+    let starter_identifier = match (starter_identifier, &manifest) {
+        (Some(identifier), _) => identifier.to_string(),
+        (None, Some(manifest)) => manifest.starter.clone(),
+        (None, None) => anyhow::bail!(
+            "Pass the starter to compare with. {:?} has no {:?} recording it",
+            dir,
+            Manifest::path_in(&dir)
+        ),
+    };
+
+    // Compare with the starter as it was used: with the same variables and features
+    let options = &UseOptions {
+        with: manifest
+            .as_ref()
+            .map(|m| m.features.clone())
+            .unwrap_or_default(),
+        ..options.clone()
+    };
+    let mut variables = manifest.map(|m| m.variables).unwrap_or_default();
+    variables.extend(options.vars.iter().cloned());
+    let vars: Vec<(String, String)> = variables.into_iter().collect();
+
+    let starter = fetch_starter(&config, &starter_identifier, options)?;
+    let files = plan_starter(&config, &starter.dir, &vars, options)?;
+    let diffs = diff_files(&files, &dir, !stat && std::io::stdout().is_terminal())?;
+
+    if diffs.is_empty() {
+        info!("{:?} matches {}", dir, starter_identifier);
+    } else if stat {
+        println!("{}", format_stat(&diffs));
+    } else {
+        for diff in &diffs {
+            match &diff.patch {
+                Some(patch) => print!("{}", patch),
+                None => println!("Binary files a/{0} and b/{0} differ", diff.path),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::copy::plan_dir;
+    use crate::filter::StarterFilter;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_diff_files() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let starter_dir = temp_dir.path().join("starter");
+        let project_dir = temp_dir.path().join("project");
+        fs::create_dir_all(starter_dir.join("src"))?;
+        fs::create_dir_all(project_dir.join("src"))?;
+        fs::write(starter_dir.join("jump-start.yaml"), "description: Test\n")?;
+        fs::write(starter_dir.join("same.txt"), "same\n")?;
+        fs::write(starter_dir.join("src/changed.txt"), "a\nb\nc\n")?;
+        fs::write(starter_dir.join("missing.txt"), "one\ntwo\n")?;
+        fs::write(project_dir.join("same.txt"), "same\n")?;
+        fs::write(project_dir.join("src/changed.txt"), "a\nB\nc\nd\n")?;
+        fs::write(project_dir.join("extra.txt"), "only in the project\n")?;
+
+        let files = plan_dir(&starter_dir, None, &StarterFilter::default())?;
+        let diffs = diff_files(&files, &project_dir, false)?;
+
+        assert_eq!(
+            diffs
+                .iter()
+                .map(|d| (d.path.as_str(), d.insertions, d.deletions))
+                .collect::<Vec<_>>(),
+            vec![("missing.txt", 0, 2), ("src/changed.txt", 2, 1)]
+        );
+        let patch = diffs[1].patch.as_deref().unwrap();
+        assert!(patch.starts_with("--- a/src/changed.txt\n+++ b/src/changed.txt\n"));
+        assert!(patch.contains("-b\n+B\n"), "{}", patch);
+        assert!(diffs[0].patch.as_deref().unwrap().contains("+++ /dev/null"));
+
+        assert_eq!(
+            format_stat(&diffs),
+            [
+                " missing.txt     | 2 --",
+                " src/changed.txt | 3 ++-",
+                " 2 files changed, 2 insertions(+), 3 deletions(-)",
+            ]
+            .join("\n")
+        );
+
+        Ok(())
+    }
+}
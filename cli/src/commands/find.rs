@@ -11,11 +11,11 @@ use std::path::PathBuf;
 pub fn find(config: Config, search_term: &str, json: bool) -> Result<()> {
     debug!("Finding {search_term}");
 
-    let pattern = make_pattern(&search_term)?;
+    let pattern = make_pattern(search_term)?;
 
     for instance in config.instances {
         debug!("Searching instance {:?}", instance.name);
-        let matches = search_instance(instance.path.clone(), &pattern)?;
+        let matches = search_instance(instance.path.clone(), &pattern, config.strict)?;
         for starter in matches {
             println!("{}", format_result(&instance, &starter, json)?);
         }
@@ -52,6 +52,7 @@ pub fn find(config: Config, search_term: &str, json: bool) -> Result<()> {
 ///         description: Some("A React component".to_string()),
 ///         default_dir: None,
 ///         preview: None,
+///         ..Default::default()
 ///     }),
 /// };
 ///
@@ -125,10 +126,10 @@ pub fn make_pattern(search_term: &str) -> Result<Regex> {
     Ok(pattern)
 }
 
-pub fn search_instance(path: PathBuf, pattern: &Regex) -> Result<Vec<LocalStarter>> {
+pub fn search_instance(path: PathBuf, pattern: &Regex, strict: bool) -> Result<Vec<LocalStarter>> {
     let mut matches = HashMap::new();
 
-    let starter_groups = parse_starters(&path)?;
+    let starter_groups = parse_starters(&path, strict)?;
     for group in starter_groups {
         for starter in group.1 {
             if pattern.is_match(&starter.path) {
@@ -138,7 +139,7 @@ pub fn search_instance(path: PathBuf, pattern: &Regex) -> Result<Vec<LocalStarte
                 // Check all starter files
                 let full_starter_path = path.join(&starter.path);
                 let starter_file_matches =
-                    search_starter_files(&starter, full_starter_path, &pattern).with_context(
+                    search_starter_files(&starter, full_starter_path, pattern).with_context(
                         || {
                             format!(
                                 "Error searching starter files for instance at path {:?}",
@@ -0,0 +1,199 @@
+use crate::config::{get_default_instance, get_instance};
+use crate::starter::parse_starters;
+use crate::{Config, JumpStartInstance};
+use anyhow::Result;
+use clap::ValueEnum;
+use log::debug;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// How `list` prints starters
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ListFormat {
+    // Aligned columns with a header
+    #[default]
+    Table,
+    // One identifier per line, as passed to `use`
+    Plain,
+    // A JSON array
+    Json,
+    // One JSON object per line
+    Ndjson,
+    // Comma-separated values with a header
+    Csv,
+}
+
+/// A starter as printed by `list`
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ListedStarter {
+    /// Identifier to pass to `use`, prefixed with the instance unless it is the default one
+    pub identifier: String,
+    pub instance: String,
+    pub group: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub main_file: Option<String>,
+    /// The starter's directory
+    pub dir: PathBuf,
+}
+
+/// Starters in every instance, or only `instance`, in groups that are `group` or nested in it.
+/// Sorted by instance, in config order, then by identifier
+pub fn list_starters(
+    config: &Config,
+    instance: Option<&str>,
+    group: Option<&str>,
+) -> Result<Vec<ListedStarter>> {
+    let instances: Vec<&JumpStartInstance> = match instance {
+        Some(name) => vec![get_instance(config, name)?],
+        None => config.instances.iter().collect(),
+    };
+    let default_instance = get_default_instance(config);
+    let group = group.map(|g| g.trim_matches('/'));
+
+    let mut listed = Vec::new();
+    for instance in instances {
+        debug!("Listing instance {:?}", instance.name);
+        let mut starters: Vec<_> = parse_starters(&instance.path, config.strict)?
+            .into_values()
+            .flatten()
+            .filter(|s| {
+                group.is_none_or(|g| s.group == g || s.group.starts_with(&format!("{}/", g)))
+            })
+            .collect();
+        starters.sort_by(|a, b| a.path.cmp(&b.path));
+
+        for starter in starters {
+            let identifier = if std::ptr::eq(instance, default_instance) {
+                starter.path.clone()
+            } else {
+                format!("{}/{}", instance.name, starter.path)
+            };
+            let config = starter.config.unwrap_or_default();
+            listed.push(ListedStarter {
+                identifier,
+                instance: instance.name.clone(),
+                dir: instance.path.join(&starter.path),
+                group: starter.group,
+                name: starter.name,
+                description: config.description,
+                main_file: config.main_file,
+            });
+        }
+    }
+    Ok(listed)
+}
+
+/// Format `starters` for printing
+///
+/// ```
+/// use jump_start::commands::list::{ListFormat, ListedStarter, format_starters};
+/// use std::path::PathBuf;
+///
+/// let starters = vec![ListedStarter {
+///     identifier: "react/app".to_string(),
+///     instance: "personal".to_string(),
+///     group: "react".to_string(),
+///     name: "app".to_string(),
+///     description: Some("A React app, with routing".to_string()),
+///     main_file: None,
+///     dir: PathBuf::from("/starters/react/app"),
+/// }];
+/// assert_eq!(format_starters(&starters, ListFormat::Plain).unwrap(), "react/app");
+/// assert_eq!(
+///     format_starters(&starters, ListFormat::Csv).unwrap(),
+///     "identifier,instance,group,name,description,main_file,dir\n\
+///      react/app,personal,react,app,\"A React app, with routing\",,/starters/react/app"
+/// );
+/// ```
+pub fn format_starters(starters: &[ListedStarter], format: ListFormat) -> Result<String> {
+    let lines: Vec<String> = match format {
+        ListFormat::Json => vec![serde_json::to_string_pretty(starters)?],
+        ListFormat::Ndjson => starters
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<_, _>>()?,
+        ListFormat::Plain => starters.iter().map(|s| s.identifier.clone()).collect(),
+        ListFormat::Csv => {
+            let mut lines =
+                vec!["identifier,instance,group,name,description,main_file,dir".to_string()];
+            for s in starters {
+                let dir = s.dir.to_string_lossy();
+                let fields = [
+                    s.identifier.as_str(),
+                    &s.instance,
+                    &s.group,
+                    &s.name,
+                    s.description.as_deref().unwrap_or_default(),
+                    s.main_file.as_deref().unwrap_or_default(),
+                    &dir,
+                ];
+                let fields: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+                lines.push(fields.join(","));
+            }
+            lines
+        }
+        ListFormat::Table => {
+            let mut rows = vec![[
+                "STARTER".to_string(),
+                "INSTANCE".to_string(),
+                "GROUP".to_string(),
+                "NAME".to_string(),
+                "DESCRIPTION".to_string(),
+                "MAIN FILE".to_string(),
+            ]];
+            for s in starters {
+                rows.push([
+                    s.identifier.clone(),
+                    s.instance.clone(),
+                    s.group.clone(),
+                    s.name.clone(),
+                    // Keep each starter on one row
+                    s.description
+                        .as_deref()
+                        .and_then(|d| d.lines().next())
+                        .unwrap_or_default()
+                        .to_string(),
+                    s.main_file.clone().unwrap_or_default(),
+                ]);
+            }
+            let width = |i: usize| rows.iter().map(|r| r[i].chars().count()).max().unwrap_or(0);
+            let widths: Vec<usize> = (0..rows[0].len()).map(width).collect();
+            rows.iter()
+                .map(|r| {
+                    r.iter()
+                        .zip(&widths)
+                        .map(|(cell, width)| format!("{:<width$}", cell))
+                        .collect::<Vec<_>>()
+                        .join("  ")
+                        .trim_end()
+                        .to_string()
+                })
+                .collect()
+        }
+    };
+    Ok(lines.join("\n"))
+}
+
+/// Quote a CSV field if it contains a comma, quote or line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Print the starters in the configured instances
+pub fn list(
+    config: Config,
+    instance: Option<&str>,
+    group: Option<&str>,
+    format: ListFormat,
+) -> Result<()> {
+    let starters = list_starters(&config, instance, group)?;
+    if !starters.is_empty() || format == ListFormat::Json {
+        println!("{}", format_starters(&starters, format)?);
+    }
+    Ok(())
+}
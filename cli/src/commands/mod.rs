@@ -1,5 +1,11 @@
+pub mod cache;
 pub mod config;
+pub mod diff;
 pub mod find;
+pub mod list;
+pub mod schema;
 pub mod storybook;
 pub mod update_readme;
+pub mod upgrade;
 pub mod r#use;
+pub mod validate;
@@ -0,0 +1,11 @@
+use crate::schema::starter_config_schema;
+use anyhow::Result;
+
+/// Print the JSON Schema of jump-start.yaml files, for editors and the YAML language server
+pub fn schema() -> Result<()> {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&starter_config_schema())?
+    );
+    Ok(())
+}
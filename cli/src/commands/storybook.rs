@@ -25,7 +25,7 @@ pub fn dev(config: Config, instance_path: Option<&str>, port: u16) -> Result<()>
     // Create temporary directory for Storybook
     let temp_dir = TempDir::new().context("Failed to create temporary directory")?;
     let temp_path = temp_dir.path();
-    setup_storybook_environment(&temp_path, &instance_path)?;
+    setup_storybook_environment(temp_path, &instance_path, config.strict)?;
 
     println!("Starting Storybook development server on port {}...", port);
     let storybook_path = temp_path.to_path_buf();
@@ -72,19 +72,22 @@ pub fn prod(config: Config, instance_path: Option<&str>, output: String) -> Resu
     // Create temporary directory for Storybook
     let temp_dir = TempDir::new().context("Failed to create temporary directory")?;
     let temp_path = temp_dir.path();
-    setup_storybook_environment(&temp_path, &instance_path)?;
+    setup_storybook_environment(temp_path, &instance_path, config.strict)?;
 
     // Make output path absolute relative to current working directory
     let cwd = std::env::current_dir().context("Failed to get current working directory")?;
     let output_path = cwd.join(&output);
-    
-    println!("Building Storybook for production to {}", output_path.display());
+
+    println!(
+        "Building Storybook for production to {}",
+        output_path.display()
+    );
     let output_arg = format!("--output-dir={}", output_path.display());
     let mut cmd = Command::new("npx");
     cmd.arg("storybook")
         .arg("build")
         .arg(output_arg)
-        .current_dir(&temp_path)
+        .current_dir(temp_path)
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit());
 
@@ -100,16 +103,19 @@ pub fn prod(config: Config, instance_path: Option<&str>, output: String) -> Resu
 
 /// Sets up the complete Storybook environment in a temporary directory
 /// This includes installing dependencies, generating config, and creating stories
-fn setup_storybook_environment(temp_dir: &Path, instance_dir: &Path) -> Result<()> {
-    println!("Setting up Storybook in temporary directory: {:?}", temp_dir);
+fn setup_storybook_environment(temp_dir: &Path, instance_dir: &Path, strict: bool) -> Result<()> {
+    println!(
+        "Setting up Storybook in temporary directory: {:?}",
+        temp_dir
+    );
     install_node_deps(temp_dir)?;
     generate_config(temp_dir)?;
-    generate_stories_in_temp(temp_dir, instance_dir)?;
+    generate_stories_in_temp(temp_dir, instance_dir, strict)?;
     Ok(())
 }
 
 /// Generates stories in the temporary directory by reading starters from the instance directory
-fn generate_stories_in_temp(temp_dir: &Path, instance_dir: &Path) -> Result<()> {
+fn generate_stories_in_temp(temp_dir: &Path, instance_dir: &Path, strict: bool) -> Result<()> {
     let stories_dir = temp_dir.join(".storybook/stories");
 
     // Clean existing stories directory (shouldn't exist in temp, but just in case)
@@ -121,7 +127,7 @@ fn generate_stories_in_temp(temp_dir: &Path, instance_dir: &Path) -> Result<()>
     create_dir_all(&stories_dir).context("Failed to create storybook stories directory")?;
 
     // Parse starters from the instance directory (not temp_dir)
-    let grouped_starters = parse_starters(instance_dir)?;
+    let grouped_starters = parse_starters(instance_dir, strict)?;
 
     // Initialize Handlebars for templating
     let mut handlebars = Handlebars::new();
@@ -148,7 +154,7 @@ fn generate_stories_in_temp(temp_dir: &Path, instance_dir: &Path) -> Result<()>
     Ok(())
 }
 
-pub fn generate_stories(instance_dir: &Path) -> Result<()> {
+pub fn generate_stories(instance_dir: &Path, strict: bool) -> Result<()> {
     let starters_dir = instance_dir;
     let stories_dir = instance_dir.join(".storybook/stories");
 
@@ -161,7 +167,7 @@ pub fn generate_stories(instance_dir: &Path) -> Result<()> {
     create_dir_all(&stories_dir).context("Failed to create storybook stories directory")?;
 
     // Parse starters from the instance
-    let grouped_starters = parse_starters(starters_dir)?;
+    let grouped_starters = parse_starters(starters_dir, strict)?;
 
     // Initialize Handlebars for templating
     let mut handlebars = Handlebars::new();
@@ -325,7 +331,7 @@ pub fn generate_starter_story(
         "starter-story",
         r#"
 import { Meta, Title } from '@storybook/blocks';
-import StarterPreview from '../../../StarterPreview';
+import StarterPreview from '{{{stories_root}}}StarterPreview';
 import files from './files.json';
 import starter from './starter.json';
 
@@ -386,8 +392,12 @@ import starter from './starter.json';
     // Get starter command
     let starter_command = get_starter_command(starter, &github_username, &github_repo);
 
+    // Stories are nested one directory per group level, plus one for the starter
+    let stories_root = "../".repeat(starter.group.split('/').count() + 2);
+
     // Render the template
     let template_data = json!({
+        "stories_root": stories_root,
         "starter": starter,
         "starter_description": starter.config.as_ref().unwrap().description,
         "github_username": github_username,
@@ -8,6 +8,20 @@ use std::env;
 use std::fs;
 use std::path::Path;
 
+/// The anchor GitHub links a heading to: lowercased, with spaces as dashes and other
+/// punctuation dropped, so "frontend/react" becomes "frontendreact"
+fn heading_anchor(heading: &str) -> String {
+    heading
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
 fn generate_readme_section(groups: &LocalStarterGroupLookup) -> String {
     let mut output: Vec<String> = Vec::new();
 
@@ -19,7 +33,11 @@ fn generate_readme_section(groups: &LocalStarterGroupLookup) -> String {
     sorted_groups.sort_by_key(|(key, _)| *key);
 
     for (group_name, _) in &sorted_groups {
-        output.push(format!("- [{}](#{})", group_name, group_name));
+        output.push(format!(
+            "- [{}](#{})",
+            group_name,
+            heading_anchor(group_name)
+        ));
     }
 
     output.push("\n---\n".to_string());
@@ -46,12 +64,10 @@ fn generate_readme_section(groups: &LocalStarterGroupLookup) -> String {
                 get_starter_command(data, &github_username, &github_repo)
             ));
 
-            if let Some(config) = &data.config {
-                if let Some(description) = &config.description {
-                    if !description.is_empty() {
-                        output.push(description.clone());
-                    }
-                }
+            if let Some(description) = data.config.as_ref().and_then(|c| c.description.as_ref())
+                && !description.is_empty()
+            {
+                output.push(description.clone());
             }
 
             output.push("---".to_string());
@@ -91,7 +107,7 @@ pub fn update_readme(config: Config, instance_path: Option<&str>) -> Result<()>
     let path = resolve_instance_path(&config, instance_path);
     println!("Using instance at {:?}", path);
 
-    let groups = parse_starters(&path)?;
+    let groups = parse_starters(&path, config.strict)?;
     let starters_section = generate_readme_section(&groups);
 
     let readme_path = Path::new(&path).join("README.md");
@@ -114,16 +130,13 @@ mod tests {
     use std::collections::HashMap;
 
     fn create_test_starter(group: &str, name: &str, description: Option<&str>) -> LocalStarter {
-        let config = if let Some(desc) = description {
-            Some(StarterConfig {
-                description: Some(desc.to_string()),
-                default_dir: None,
-                main_file: None,
-                preview: None,
-            })
-        } else {
-            None
-        };
+        let config = description.map(|desc| StarterConfig {
+            description: Some(desc.to_string()),
+            default_dir: None,
+            main_file: None,
+            preview: None,
+            ..Default::default()
+        });
 
         LocalStarter {
             path: format!("{}/{}", group, name),
@@ -210,7 +223,10 @@ mod tests {
         groups.insert("react".to_string(), vec![starter.clone()]);
 
         let jump_start_command = get_starter_command(&starter, "testuser", "testrepo");
-        assert_eq!(jump_start_command, "jump-start use @testuser/testrepo/react/app");
+        assert_eq!(
+            jump_start_command,
+            "jump-start use @testuser/testrepo/react/app"
+        );
 
         let default_repo_command = get_starter_command(&starter, "testuser", "jump-start");
         assert_eq!(default_repo_command, "jump-start use @testuser/react/app");
@@ -231,6 +247,27 @@ mod tests {
         assert!(result.contains("jump-start use react/app"));
     }
 
+    #[test]
+    fn test_generate_readme_section_nested_group() {
+        use crate::starter::get_starter_command;
+
+        let mut groups = HashMap::new();
+        let starter = create_test_starter("frontend/react", "dashboard", None);
+        groups.insert("frontend/react".to_string(), vec![starter.clone()]);
+
+        let result = generate_readme_section(&groups);
+
+        assert!(result.contains("- [frontend/react](#frontendreact)"));
+        assert!(result.contains("### frontend/react"));
+        assert!(result.contains("frontend/react/**dashboard**"));
+
+        // The repository is needed to tell the nested group apart from it
+        assert_eq!(
+            get_starter_command(&starter, "testuser", "jump-start"),
+            "jump-start use @testuser/jump-start/frontend/react/dashboard"
+        );
+    }
+
     #[test]
     fn test_generate_readme_section_no_description() {
         let mut groups = HashMap::new();
@@ -0,0 +1,366 @@
+use crate::cache::now_secs;
+use crate::commands::r#use::{UseOptions, fetch_remote_starter, fetch_starter, plan_starter};
+use crate::copy::PlannedFile;
+use crate::git;
+use crate::manifest::{Manifest, hash_contents};
+use crate::{Config, RemoteStarter};
+use anyhow::{Context, Result};
+use log::{debug, info, warn};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// What upgrading a single file does
+#[derive(Debug, PartialEq)]
+enum FileUpgrade {
+    /// Nothing to do: the file is unchanged in the starter, or already matches it
+    Unchanged,
+    /// Only the starter changed the file, so its new version is written
+    Updated(Vec<u8>),
+    /// Both changed the file and the changes were merged cleanly
+    Merged(Vec<u8>),
+    /// Both changed the file; it is written with conflict markers
+    Conflict(Vec<u8>),
+    /// Both changed a binary file, which is left as it is
+    BinaryConflict,
+    /// The file is new in the starter
+    Added(Vec<u8>),
+    /// The file was deleted from the project and is not restored
+    DeletedLocally,
+    /// The file was removed from the starter and is deleted from the project
+    Removed,
+    /// The file was removed from the starter but changed in the project, so it is kept
+    KeptRemoved,
+}
+
+/// Work out how to upgrade one file.
+///
+/// `recorded` is the hash of the file when the project was created (or last upgraded), `base`
+/// the starter's version of the file at that time, if it could be fetched, and `local` and
+/// `upstream` the file in the project and in the starter now.
+fn upgrade_file(
+    recorded: Option<&str>,
+    base: Option<&[u8]>,
+    local: Option<&[u8]>,
+    upstream: Option<&[u8]>,
+) -> FileUpgrade {
+    let matches_recorded = |contents: &[u8]| recorded == Some(hash_contents(contents).as_str());
+
+    match (recorded, local, upstream) {
+        (_, Some(local), Some(upstream)) if local == upstream => FileUpgrade::Unchanged,
+        (None, None, Some(upstream)) => FileUpgrade::Added(upstream.to_vec()),
+        (None, Some(local), Some(upstream)) => merge(None, local, upstream),
+        // Removed on both sides
+        (Some(_), None, None) => FileUpgrade::Unchanged,
+        (Some(_), None, Some(_)) => FileUpgrade::DeletedLocally,
+        (Some(_), Some(local), None) if matches_recorded(local) => FileUpgrade::Removed,
+        (Some(_), Some(_), None) => FileUpgrade::KeptRemoved,
+        (Some(_), Some(local), Some(upstream)) => {
+            if matches_recorded(local) {
+                FileUpgrade::Updated(upstream.to_vec())
+            } else if matches_recorded(upstream) {
+                FileUpgrade::Unchanged
+            } else {
+                // The base is only trustworthy if it is what was originally written
+                merge(base.filter(|b| matches_recorded(b)), local, upstream)
+            }
+        }
+        (None, _, None) => FileUpgrade::Unchanged,
+    }
+}
+
+/// Three-way merge `local` and `upstream` text. Without a `base`, every difference conflicts.
+fn merge(base: Option<&[u8]>, local: &[u8], upstream: &[u8]) -> FileUpgrade {
+    let (Ok(base), Ok(local), Ok(upstream)) = (
+        std::str::from_utf8(base.unwrap_or_default()),
+        std::str::from_utf8(local),
+        std::str::from_utf8(upstream),
+    ) else {
+        return FileUpgrade::BinaryConflict;
+    };
+    match diffy::merge(base, local, upstream) {
+        Ok(merged) => FileUpgrade::Merged(merged.into_bytes()),
+        Err(conflicted) => FileUpgrade::Conflict(conflicted.into_bytes()),
+    }
+}
+
+/// Fetch the starter as it was when the project was created, from the commit in `manifest`.
+///
+/// Returns `None` if the commit is unknown or can't be fetched, in which case files changed on
+/// both sides are merged without a base. The `TempDir` holds exported local starters.
+fn fetch_base(
+    config: &Config,
+    manifest: &Manifest,
+    options: &UseOptions,
+) -> Result<Option<(PathBuf, Option<TempDir>)>> {
+    let Some(commit) = &manifest.commit else {
+        return Ok(None);
+    };
+
+    if RemoteStarter::is_remote_identifier(&manifest.starter) {
+        let mut starter = RemoteStarter::from_path(&manifest.starter)
+            .ok_or_else(|| anyhow::anyhow!("Invalid starter {:?} in manifest", manifest.starter))?;
+        starter.git_ref = Some(commit.clone());
+        let (dir, _) = fetch_remote_starter(config, &starter, options)?;
+        Ok(Some((dir, None)))
+    } else {
+        let options = UseOptions {
+            instance: manifest.instance.clone(),
+            ..options.clone()
+        };
+        let current = fetch_starter(config, &manifest.starter, &options)?;
+        let temp_dir = tempfile::tempdir()?;
+        git::export_dir_at(&current.dir, commit, temp_dir.path())?;
+        Ok(Some((temp_dir.path().to_path_buf(), Some(temp_dir))))
+    }
+}
+
+/// Index planned files by their manifest key
+fn by_key(files: Vec<PlannedFile>) -> BTreeMap<String, PlannedFile> {
+    files.into_iter().map(|f| (f.key(), f)).collect()
+}
+
+/// Bring the project in `dir`, created with `use`, up to date with `starter_identifier`.
+///
+/// The starter's changes since the project was created are three-way merged with local edits,
+/// using the commit and file hashes recorded in the project's manifest.
+pub fn upgrade(
+    config: Config,
+    starter_identifier: &str,
+    dir: Option<&str>,
+    options: &UseOptions,
+) -> Result<()> {
+    let dir = PathBuf::from(dir.unwrap_or("."));
+    let manifest = Manifest::load(&dir)?.ok_or_else(|| {
+        anyhow::anyhow!(
+            "No {:?} found. `upgrade` compares against the manifest `jump-start use` writes, so \
+             projects created with `--no-manifest` can't be upgraded",
+            Manifest::path_in(&dir)
+        )
+    })?;
+
+    // Features selected at `use` time stay selected
+    let options = &UseOptions {
+        with: manifest.features.clone(),
+        ..options.clone()
+    };
+
+    // Variables passed now win over the ones recorded at `use` time
+    let mut variables = manifest.variables.clone();
+    variables.extend(options.vars.iter().cloned());
+    let vars: Vec<(String, String)> = variables.clone().into_iter().collect();
+
+    let upstream = fetch_starter(&config, starter_identifier, options)?;
+    let upstream_files = by_key(plan_starter(&config, &upstream.dir, &vars, options)?);
+
+    let base = match fetch_base(&config, &manifest, options) {
+        Ok(base) => base,
+        Err(e) => {
+            warn!(
+                "Could not fetch the starter as of {:?}: {:#}",
+                manifest.commit, e
+            );
+            None
+        }
+    };
+    let base_files = match &base {
+        Some((base_dir, _)) => match plan_starter(&config, base_dir, &vars, options) {
+            Ok(files) => by_key(files),
+            Err(e) => {
+                warn!(
+                    "Could not read the starter as of {:?}: {:#}",
+                    manifest.commit, e
+                );
+                BTreeMap::new()
+            }
+        },
+        None => BTreeMap::new(),
+    };
+    let keys: BTreeSet<&String> = manifest.files.keys().chain(upstream_files.keys()).collect();
+    let mut changed = 0;
+    let mut conflicts = Vec::new();
+    for key in keys {
+        let path = dir.join(key);
+        let local = if path.is_file() {
+            Some(fs::read(&path)?)
+        } else {
+            None
+        };
+        let upstream = upstream_files.get(key).map(|f| f.contents()).transpose()?;
+        let base = base_files.get(key).map(|f| f.contents()).transpose()?;
+
+        let action = upgrade_file(
+            manifest.files.get(key).map(String::as_str),
+            base.as_deref(),
+            local.as_deref(),
+            upstream.as_deref(),
+        );
+        let label = match &action {
+            FileUpgrade::Unchanged => {
+                debug!("{} is unchanged", key);
+                continue;
+            }
+            FileUpgrade::Updated(contents) => {
+                write_file(&path, contents)?;
+                "updated"
+            }
+            FileUpgrade::Merged(contents) => {
+                write_file(&path, contents)?;
+                "merged"
+            }
+            FileUpgrade::Conflict(contents) => {
+                write_file(&path, contents)?;
+                conflicts.push(key.as_str());
+                "conflict"
+            }
+            FileUpgrade::BinaryConflict => {
+                conflicts.push(key.as_str());
+                "conflict (binary, kept yours)"
+            }
+            FileUpgrade::Added(contents) => {
+                write_file(&path, contents)?;
+                "added in starter"
+            }
+            FileUpgrade::DeletedLocally => "deleted locally, not restored",
+            FileUpgrade::Removed => {
+                fs::remove_file(&path)?;
+                "removed from starter"
+            }
+            FileUpgrade::KeptRemoved => "removed from starter, kept your changes",
+        };
+        info!("{:<32} {}", label, key);
+        changed += 1;
+    }
+
+    // The starter's current files are the base for the next upgrade
+    let mut files = BTreeMap::new();
+    for (key, file) in &upstream_files {
+        files.insert(key.clone(), hash_contents(&file.contents()?));
+    }
+    Manifest {
+        created_at: manifest.created_at,
+        upgraded_at: Some(now_secs()),
+        files,
+        variables,
+        features: manifest.features.clone(),
+        ..upstream.source
+    }
+    .save(&dir)
+    .context("Updating manifest")?;
+
+    if changed == 0 {
+        info!("{:?} is up to date with {}", dir, starter_identifier);
+    }
+    if !conflicts.is_empty() {
+        warn!(
+            "{} files have conflicts. Resolve them before committing: {}",
+            conflicts.len(),
+            conflicts.join(", ")
+        );
+        if base_files.is_empty() {
+            warn!(
+                "The starter as of the project's creation was unavailable, so conflicts cover whole files"
+            );
+        }
+    }
+    Ok(())
+}
+
+fn write_file(path: &Path, contents: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents).with_context(|| format!("Writing {:?}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upgrade_text(
+        recorded: Option<&str>,
+        base: Option<&str>,
+        local: Option<&str>,
+        upstream: Option<&str>,
+    ) -> FileUpgrade {
+        let recorded = recorded.map(|r| hash_contents(r.as_bytes()));
+        upgrade_file(
+            recorded.as_deref(),
+            base.map(str::as_bytes),
+            local.map(str::as_bytes),
+            upstream.map(str::as_bytes),
+        )
+    }
+
+    #[test]
+    fn test_upgrade_file_one_side_changed() {
+        let v1 = "a\nb\nc\n";
+        let v2 = "a\nB\nc\n";
+
+        assert_eq!(
+            upgrade_text(Some(v1), Some(v1), Some(v1), Some(v2)),
+            FileUpgrade::Updated(v2.as_bytes().to_vec())
+        );
+        assert_eq!(
+            upgrade_text(Some(v1), Some(v1), Some(v2), Some(v1)),
+            FileUpgrade::Unchanged
+        );
+        assert_eq!(
+            upgrade_text(None, None, None, Some(v2)),
+            FileUpgrade::Added(v2.as_bytes().to_vec())
+        );
+        assert_eq!(
+            upgrade_text(Some(v1), Some(v1), None, Some(v2)),
+            FileUpgrade::DeletedLocally
+        );
+        assert_eq!(
+            upgrade_text(Some(v1), Some(v1), None, None),
+            FileUpgrade::Unchanged
+        );
+        assert_eq!(
+            upgrade_text(Some(v1), Some(v1), Some(v1), None),
+            FileUpgrade::Removed
+        );
+        assert_eq!(
+            upgrade_text(Some(v1), Some(v1), Some(v2), None),
+            FileUpgrade::KeptRemoved
+        );
+    }
+
+    #[test]
+    fn test_upgrade_file_both_changed() {
+        let base = "a\nb\nc\nd\ne\n";
+        let local = "A\nb\nc\nd\ne\n";
+        let upstream = "a\nb\nc\nd\nE\n";
+
+        assert_eq!(
+            upgrade_text(Some(base), Some(base), Some(local), Some(upstream)),
+            FileUpgrade::Merged(b"A\nb\nc\nd\nE\n".to_vec())
+        );
+
+        let FileUpgrade::Conflict(conflicted) =
+            upgrade_text(Some(base), Some(base), Some(local), Some("Z\nb\nc\nd\ne\n"))
+        else {
+            panic!("expected a conflict");
+        };
+        let conflicted = String::from_utf8(conflicted).unwrap();
+        assert!(conflicted.contains("<<<<<<< ours\nA\n"), "{}", conflicted);
+        assert!(conflicted.contains(">>>>>>> theirs"), "{}", conflicted);
+
+        // A base that doesn't match the recorded hash is ignored
+        assert!(matches!(
+            upgrade_text(Some(base), Some("x\n"), Some(local), Some(upstream)),
+            FileUpgrade::Conflict(_)
+        ));
+        assert_eq!(
+            upgrade_file(
+                Some("sha256:0"),
+                None,
+                Some(&[0, 159, 146, 150]),
+                Some(&[0, 1])
+            ),
+            FileUpgrade::BinaryConflict
+        );
+    }
+}
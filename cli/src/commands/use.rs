@@ -1,62 +1,607 @@
 use crate::JumpStartInstance;
+use crate::cache::format_size;
+use crate::cache::{CacheMetadata, CachePolicy, DEFAULT_CACHE_TTL, now_secs};
+use crate::config::{
+    get_cache_dir, get_config_path, get_default_instance, get_instance, is_trusted_source,
+};
+use crate::copy::{ConflictPolicy, FileAction, PlannedFile, copy_files, plan_actions, plan_dir};
+use crate::degit::{self, DegitAction};
+use crate::filter::StarterFilter;
+use crate::git;
+use crate::hooks::{HookEnv, run_hooks};
+use crate::host::{Host, resolve_host};
+use crate::manifest::{FileHashes, MANIFEST_FILE_NAME, Manifest, hash_contents};
 use crate::starter::StarterConfig;
-use crate::{Config, LocalStarter, RemoteStarter, config::get_default_instance};
+use crate::template::{TemplateRenderer, resolve_variables};
+use crate::{Config, LocalStarter, RemoteStarter};
 use anyhow::{Context, Result};
-use directories::ProjectDirs;
+use clap::ValueEnum;
 use flate2::read::GzDecoder;
 use log::{debug, info, warn};
+use reqwest::StatusCode;
 use reqwest::blocking::Client;
+use reqwest::header::{
+    ETAG, HeaderName, HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use serde_json::json;
 use std::fs::{self, File};
-use std::io;
+use std::io::{self, BufRead, IsTerminal, Write};
 use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
 use tar::Archive;
 
-pub fn r#use(config: Config, starter_identifier: &str, dest: Option<&str>) -> Result<()> {
-    let instance = get_default_instance(&config);
+/// How remote starters are fetched
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CloneMode {
+    // Download the repository's tarball over HTTPS
+    #[default]
+    Tar,
+    // Clone the repository with git, e.g. for private repositories over SSH
+    Git,
+}
+
+/// Options for `use` beyond the starter and the destination
+#[derive(Debug, Default, Clone)]
+pub struct UseOptions {
+    /// Template variables supplied on the command line as KEY=VALUE pairs
+    pub vars: Vec<(String, String)>,
+    /// How remote starters are fetched
+    pub mode: CloneMode,
+    /// Check remote starters for changes even if the cached copy is recent
+    pub refresh: bool,
+    /// Instance to take local starters from, instead of the default instance
+    pub instance: Option<String>,
+    /// What to do with files that already exist in the destination
+    pub on_conflict: ConflictPolicy,
+    /// Print what would be written instead of writing it
+    pub dry_run: bool,
+    /// Print the dry-run plan as JSON, one file per line
+    pub json: bool,
+    /// Don't write a .jump-start.json manifest recording the starter into the destination
+    pub no_manifest: bool,
+    /// Don't run the starter's post_use hooks
+    pub no_hooks: bool,
+    /// Use remote starters from sources not in `trusted_sources` without asking
+    pub trust: bool,
+    /// Optional features of the starter to include, as declared under `features`
+    pub with: Vec<String>,
+    /// Open the starter's main file in $VISUAL or $EDITOR after copying
+    pub open: bool,
+}
+
+/// A starter's files, found in its instance or downloaded into the cache
+pub struct FetchedStarter {
+    /// Directory holding the starter's files
+    pub dir: PathBuf,
+    /// Where the starter came from, as recorded in manifests. `files` is left empty
+    pub source: Manifest,
+}
 
-    if starter_identifier.starts_with("@") {
-        let starter = RemoteStarter::from_path(starter_identifier).unwrap();
+pub fn r#use(
+    config: Config,
+    starter_identifier: &str,
+    dest: Option<&str>,
+    options: &UseOptions,
+) -> Result<()> {
+    let fetched = fetch_starter(&config, starter_identifier, options)?;
+    let starter_config = read_starter_config(&fetched.dir)?.unwrap_or_default();
+    let final_dest = get_final_dest(&fetched.dir, dest)?;
+    let source = fetched.source;
+    let manifest = (!options.no_manifest).then(|| Manifest {
+        created_at: now_secs(),
+        variables: options.vars.iter().cloned().collect(),
+        features: options.with.clone(),
+        ..source.clone()
+    });
+    copy_starter(&config, &fetched.dir, &final_dest, manifest, options)
+        .context("Copying dir contents")?;
+    report_copied(starter_identifier, &final_dest, options);
+    if !options.json {
+        report_unselected_features(&starter_config, options);
+    }
+
+    if let Some(git_ref) = &source.git_ref
+        && !options.json
+    {
+        match &source.commit {
+            Some(commit) => info!("Resolved {} to commit {}", git_ref, commit),
+            None => info!("Could not resolve {} to a commit", git_ref),
+        }
+    }
+
+    let hooks = starter_config
+        .hooks
+        .as_ref()
+        .map(|h| h.post_use.as_slice())
+        .unwrap_or_default();
+    run_post_use_hooks(
+        starter_identifier,
+        &fetched.dir,
+        &final_dest,
+        hooks,
+        options,
+    )?;
+
+    if !options.dry_run {
+        print_next_steps(&starter_config, &final_dest, options.open)?;
+    }
+    Ok(())
+}
+
+/// Find the starter `starter_identifier` refers to, downloading remote starters into the cache
+pub fn fetch_starter(
+    config: &Config,
+    starter_identifier: &str,
+    options: &UseOptions,
+) -> Result<FetchedStarter> {
+    if RemoteStarter::is_remote_identifier(starter_identifier) {
+        if options.instance.is_some() {
+            anyhow::bail!("--instance only applies to local starters");
+        }
+        let starter = RemoteStarter::from_path(starter_identifier).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Invalid starter identifier {:?}, expected @owner/group/name or \
+                 @owner/repo/group/name, optionally followed by @REF or #SHA",
+                starter_identifier
+            )
+        })?;
         debug!("Remote starter {:?}", starter);
-        let mode = "tar";
 
-        let ultimate_dest = clone_remote_starter(starter, dest, mode)
-            .with_context(|| format!("Cloning remote starter"))?;
-        info!("{} copied to {:?}", starter_identifier, ultimate_dest)
+        let host = resolve_host(config, starter.host.as_deref())?;
+        // Without a terminal to ask on, untrusted sources are refused before downloading anything
+        let interactive = io::stdin().is_terminal();
+        if !interactive {
+            check_trust(config, &starter, &host, options, &[], None)?;
+        }
+        let (dir, commit) =
+            fetch_remote_starter(config, &starter, options).context("Cloning remote starter")?;
+        // Otherwise the starter is only downloaded into the cache before asking, so the question
+        // can list the hooks that trusting it runs
+        if interactive {
+            let hooks = match read_starter_config(&dir)?.and_then(|c| c.hooks) {
+                Some(hooks) if !options.no_hooks => hooks.post_use,
+                _ => Vec::new(),
+            };
+            check_trust(
+                config,
+                &starter,
+                &host,
+                options,
+                &hooks,
+                Some(&mut io::stdin().lock()),
+            )?;
+        }
+        Ok(FetchedStarter {
+            dir,
+            source: Manifest {
+                starter: starter_identifier.to_string(),
+                repository: Some(format!(
+                    "{}/{}/{}",
+                    host.name, starter.github_username, starter.github_repo
+                )),
+                git_ref: Some(starter.archive_ref().to_string()),
+                commit,
+                ..Default::default()
+            },
+        })
     } else {
-        let starter = LocalStarter::from_path(starter_identifier).unwrap();
-        debug!("Local starter {:?}", starter);
+        let (instance, starter) =
+            resolve_local_starter(config, starter_identifier, options.instance.as_deref())?;
+        debug!(
+            "Local starter {:?} from instance {:?}",
+            starter, instance.name
+        );
+
+        let dir = instance.path.join(&starter.path);
+        if !dir.is_dir() {
+            anyhow::bail!(
+                "Starter {:?} not found in instance {:?} ({:?})",
+                starter.path,
+                instance.name,
+                instance.path
+            );
+        }
+        Ok(FetchedStarter {
+            dir,
+            source: Manifest {
+                starter: starter_identifier.to_string(),
+                instance: Some(instance.name.clone()),
+                // Instances are usually git repositories, so record the commit for manifests
+                commit: git::head_commit(&instance.path).ok(),
+                ..Default::default()
+            },
+        })
+    }
+}
+
+/// Print the starter's instructions and the path of its main file, opening it with --open
+fn print_next_steps(starter_config: &StarterConfig, dest: &Path, open: bool) -> Result<()> {
+    if let Some(instructions) = &starter_config.instructions
+        && !instructions.trim().is_empty()
+    {
+        info!("\nNext steps:\n\n{}\n", instructions.trim_end());
+    }
+
+    let Some(main_file) = &starter_config.main_file else {
+        if open {
+            warn!("Nothing to open: the starter doesn't set mainFile in its jump-start.yaml");
+        }
+        return Ok(());
+    };
+    let path = fs::canonicalize(dest)?.join(main_file);
+    if !path.is_file() {
+        warn!("The starter's mainFile {:?} was not copied", main_file);
+        return Ok(());
+    }
+    info!("Main file: {}", path.display());
 
-        let ultimate_dest = clone_local_starter(instance, starter, dest)
-            .with_context(|| format!("Cloning local starter"))?;
-        info!("{} copied to {:?}", starter_identifier, ultimate_dest)
+    if open {
+        let editor = ["VISUAL", "EDITOR"]
+            .iter()
+            .find_map(|var| std::env::var(var).ok().filter(|v| !v.trim().is_empty()));
+        match editor {
+            Some(editor) => {
+                if let Err(e) = open_with(&editor, &path) {
+                    warn!("{:#}", e);
+                }
+            }
+            None => warn!("Set $VISUAL or $EDITOR to open the main file"),
+        }
     }
+    Ok(())
+}
 
+/// Open `path` with the `editor` command, which may include arguments (e.g. "code --wait"),
+/// waiting for it to exit
+fn open_with(editor: &str, path: &Path) -> Result<()> {
+    let mut args = editor.split_whitespace();
+    let program = args
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("No editor given"))?;
+    let status = Command::new(program)
+        .args(args)
+        .arg(path)
+        .status()
+        .with_context(|| format!("Could not run editor {:?}", editor))?;
+    if !status.success() {
+        anyhow::bail!("Editor {:?} exited with {}", editor, status);
+    }
     Ok(())
 }
 
-fn download_tar(url: &String, dest: &Path) -> Result<PathBuf> {
+/// Make sure the source of the remote `starter` is trusted before using it, asking on `input` if
+/// it isn't in `trusted_sources` and --trust wasn't passed. Without `input`, untrusted sources
+/// are refused. `hooks` are the post_use hooks trusting the starter would run
+fn check_trust(
+    config: &Config,
+    starter: &RemoteStarter,
+    host: &Host,
+    options: &UseOptions,
+    hooks: &[String],
+    input: Option<&mut dyn BufRead>,
+) -> Result<()> {
+    let (owner, repo) = (&starter.github_username, &starter.github_repo);
+    if options.trust || is_trusted_source(config, &host.name, owner, repo) {
+        return Ok(());
+    }
+
+    let source = format!("{}/{}/{}", host.name, owner, repo);
+    let url = match options.mode {
+        CloneMode::Tar => host.archive_url(owner, repo, starter.archive_ref()),
+        CloneMode::Git => host.clone_url(owner, repo),
+    };
+    let hint = format!(
+        "Pass --trust to use it anyway, or add \"{}/{}\" to \"trusted_sources\" in {:?}",
+        owner,
+        repo,
+        get_config_path()
+    );
+    match input {
+        None => anyhow::bail!(
+            "{} is not a trusted source. Check that it is the repository you meant ({}). {}",
+            source,
+            url,
+            hint
+        ),
+        Some(input) => {
+            if !confirm_trust(&trust_warning(&source, &url, hooks), input)? {
+                anyhow::bail!("Aborted: {} is not a trusted source. {}", source, hint);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// What to say before asking to trust a starter from the untrusted `source` at `url`, whose
+/// `hooks` run once it is trusted
+fn trust_warning(source: &str, url: &str, hooks: &[String]) -> String {
+    let mut lines = vec![
+        format!("{} is not in your trusted sources.", source),
+        format!("The starter comes from {}", url),
+    ];
+    if !hooks.is_empty() {
+        lines.push("Trusting it also runs its post_use hooks in the destination:".to_string());
+        lines.extend(hooks.iter().map(|hook| format!("  {}", hook)));
+    }
+    lines.join("\n")
+}
+
+/// Print `warning` and ask whether to trust the starter anyway. Both go to stderr, so they
+/// aren't mixed into output on stdout, like the plan printed by `--dry-run --json`
+fn confirm_trust(warning: &str, input: &mut dyn BufRead) -> Result<bool> {
+    eprintln!("{}", warning);
+    loop {
+        eprint!("Trust it and continue? [y/N] ");
+        io::stderr().flush()?;
+
+        let mut answer = String::new();
+        if input.read_line(&mut answer)? == 0 {
+            return Ok(false);
+        }
+        match answer.trim().to_lowercase().as_str() {
+            "y" | "yes" => return Ok(true),
+            "" | "n" | "no" => return Ok(false),
+            _ => continue,
+        }
+    }
+}
+
+/// Mention the starter's features that weren't selected with --with
+fn report_unselected_features(starter_config: &StarterConfig, options: &UseOptions) {
+    let unselected: Vec<String> = starter_config
+        .features
+        .iter()
+        .flatten()
+        .filter(|(name, _)| !options.with.contains(name))
+        .map(|(name, feature)| match &feature.description {
+            Some(description) => format!("{} ({})", name, description.trim()),
+            None => name.clone(),
+        })
+        .collect();
+    if !unselected.is_empty() {
+        info!(
+            "Optional features not included: {}. Add them with --with NAME",
+            unselected.join(", ")
+        );
+    }
+}
+
+fn report_copied(starter_identifier: &str, dest: &Path, options: &UseOptions) {
+    if options.json {
+        return;
+    }
+    if options.dry_run {
+        info!(
+            "Dry run: {} would be copied to {:?}. Nothing was written",
+            starter_identifier, dest
+        );
+    } else {
+        info!("{} copied to {:?}", starter_identifier, dest);
+    }
+}
+
+/// Run the starter's post_use hooks in `dest`, unless this is a dry run or --no-hooks was passed
+fn run_post_use_hooks(
+    starter_identifier: &str,
+    starter_dir: &Path,
+    dest: &Path,
+    hooks: &[String],
+    options: &UseOptions,
+) -> Result<()> {
+    if hooks.is_empty() {
+        return Ok(());
+    }
+    if options.dry_run {
+        if !options.json {
+            info!(
+                "Would run {} post_use hooks: {}",
+                hooks.len(),
+                hooks.join("; ")
+            );
+        }
+        return Ok(());
+    }
+    if options.no_hooks {
+        info!(
+            "Skipping {} post_use hooks (--no-hooks): {}",
+            hooks.len(),
+            hooks.join("; ")
+        );
+        return Ok(());
+    }
+
+    fs::create_dir_all(dest)?;
+    let dest = fs::canonicalize(dest)?;
+    let starter_name = starter_dir
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .unwrap_or_default();
+    let env = HookEnv {
+        starter: starter_identifier,
+        starter_name: &starter_name,
+        starter_dir,
+        dest: &dest,
+    };
+    run_hooks("post_use", hooks, &env).with_context(|| {
+        format!(
+            "{} was copied to {:?}, but its hooks did not all succeed",
+            starter_identifier, dest
+        )
+    })
+}
+
+/// Print what copying `files` into `dest` would do to each file, with sizes
+fn print_plan(files: &[PlannedFile], dest: &Path, options: &UseOptions) -> Result<()> {
+    let actions = plan_actions(files, dest, options.on_conflict)?;
+
+    for (file, action) in &actions {
+        let size = file.size()?;
+        if options.json {
+            let existing_size = fs::metadata(dest.join(&file.path))
+                .ok()
+                .filter(|m| m.is_file())
+                .map(|m| m.len());
+            let result_json = json!({
+                "action": action,
+                "path": file.path,
+                "dest": dest.join(&file.path),
+                "size": size,
+                "existing_size": existing_size,
+            });
+            println!("{}", serde_json::to_string(&result_json)?);
+        } else {
+            println!(
+                "{:<9} {:>10}  {}",
+                action,
+                format_size(size),
+                dest.join(&file.path).display()
+            );
+        }
+    }
+
+    let conflicts = actions
+        .iter()
+        .filter(|(_, action)| *action == FileAction::Conflict)
+        .count();
+    if !options.json && conflicts > 0 && options.on_conflict == ConflictPolicy::Abort {
+        info!(
+            "{} files conflict with existing files, so `use` would abort. Pass --on-conflict to decide what to do with them",
+            conflicts
+        );
+    }
+    Ok(())
+}
+
+/// Download the tarball at `url` into `dest`, reusing the cached copy according to `policy`.
+///
+/// Stale copies are revalidated with a conditional request using the ETag and Last-Modified
+/// headers of the previous download. If the remote can't be reached, a stale copy is used with
+/// a warning.
+///
+/// `auth` is the header that authenticates the request, for private repositories.
+fn download_tar(
+    url: &str,
+    dest: &Path,
+    policy: &CachePolicy,
+    auth: Option<(HeaderName, HeaderValue)>,
+) -> Result<PathBuf> {
     fs::create_dir_all(dest)?;
 
     let file_path = dest.join("HEAD.tar.gz");
-    // TODO is this the behavior I want?
-    if file_path.exists() {
-        debug!("{} already exists locally", file_path.display());
+    let metadata = if file_path.exists() {
+        CacheMetadata::load(&file_path).filter(|m| m.url == url)
+    } else {
+        None
+    };
+
+    if let Some(metadata) = &metadata
+        && metadata.is_fresh(policy)
+    {
+        debug!(
+            "{} already exists locally and is {}s old",
+            file_path.display(),
+            metadata.age().as_secs()
+        );
         return Ok(file_path);
     }
 
-    info!("Downloading {} to {}", url, file_path.display());
     debug!("Starting download for tarball from {}", url);
 
     let client = Client::new();
-    let mut response = client.get(url).send()?;
+    let mut request = client.get(url);
+    let authenticated = auth.is_some();
+    if let Some((name, value)) = auth {
+        debug!("Authenticating with the {} header", name);
+        request = request.header(name, value);
+    }
+    if let Some(metadata) = &metadata {
+        if let Some(etag) = &metadata.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &metadata.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    let mut response = match request.send() {
+        Ok(response) => response,
+        Err(e) if metadata.is_some() => {
+            warn!(
+                "Could not reach {} ({}), using cached copy from {}s ago",
+                url,
+                e,
+                metadata.as_ref().unwrap().age().as_secs()
+            );
+            return Ok(file_path);
+        }
+        Err(e) => return Err(e.into()),
+    };
 
-    if !response.status().is_success() {
-        warn!("HTTP request failed with status: {}", response.status());
-        anyhow::bail!("Failed to download tar: {}", url);
+    if response.status() == StatusCode::NOT_MODIFIED
+        && let Some(mut metadata) = metadata
+    {
+        debug!("{} has not changed since the last download", url);
+        metadata.fetched_at = now_secs();
+        metadata.save(&file_path)?;
+        return Ok(file_path);
     }
 
-    let mut file = File::create(&file_path)?;
+    let status = response.status();
+    if !status.is_success() {
+        warn!("HTTP request failed with status: {}", status);
+        if metadata.is_some() && status.is_server_error() {
+            warn!("Using cached copy of {}", url);
+            return Ok(file_path);
+        }
+        match (status, authenticated) {
+            (StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN, true) => anyhow::bail!(
+                "Access denied ({}) to {}. The token was rejected or can't read this repository",
+                status,
+                url
+            ),
+            (StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN, false) => anyhow::bail!(
+                "Access denied ({}) to {}. Set a token for this host to download private repositories",
+                status,
+                url
+            ),
+            (StatusCode::NOT_FOUND, true) => anyhow::bail!(
+                "Not found ({}): {}. Check the repository and ref exist and that the token can read them",
+                status,
+                url
+            ),
+            // Hosts answer 404 for private repositories to hide that they exist
+            (StatusCode::NOT_FOUND, false) => anyhow::bail!(
+                "Not found ({}): {}. Check the repository and ref exist, or set a token for this host if the repository is private",
+                status,
+                url
+            ),
+            _ => anyhow::bail!("Failed to download tar ({}): {}", status, url),
+        }
+    }
+
+    info!("Downloading {} to {}", url, file_path.display());
+
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from)
+    };
+    let new_metadata = CacheMetadata {
+        url: url.to_string(),
+        etag: header(ETAG),
+        last_modified: header(LAST_MODIFIED),
+        fetched_at: now_secs(),
+    };
+
+    // Download to a temporary file so an interrupted download doesn't clobber the cached copy
+    let partial_path = dest.join("HEAD.tar.gz.partial");
+    let mut file = File::create(&partial_path)?;
     io::copy(&mut response, &mut file)?;
+    fs::rename(&partial_path, &file_path)?;
+    new_metadata.save(&file_path)?;
 
     Ok(file_path)
 }
@@ -144,70 +689,351 @@ fn extract_tar_subdir(tar_path: &Path, subdir: &str, dest: &Path) -> Result<()>
     }
 }
 
-/// Recursively copy the directory `src` to `dest`, excluding configuration files
-fn copy_dir_contents(src: &Path, dest: &Path) -> Result<()> {
-    fs::create_dir_all(dest)?;
-    for entry in fs::read_dir(src)? {
-        let entry = entry?;
-        let file_type = entry.file_type()?;
-        let src_path = entry.path();
-        let file_name = entry.file_name();
-        let dst_path = dest.join(&file_name);
-
-        // Skip configuration files
-        if file_name == "jump-start.yaml" || file_name == "degit.json" {
+/// Read the commit a GitHub tarball was created from.
+///
+/// `git archive` stores the commit in a pax global header. Failing that, the root directory is
+/// checked, which is named like "jump-start-b3c8d936025b11b9a57cfac99e0decb9f908042e/" for
+/// tarballs of HEAD or a commit.
+fn read_tar_commit(tar_path: &Path) -> Result<Option<String>> {
+    let is_sha = |s: &str| s.len() == 40 && s.chars().all(|c| c.is_ascii_hexdigit());
+
+    let tar_file = File::open(tar_path)?;
+    let mut archive = Archive::new(GzDecoder::new(tar_file));
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry.header().entry_type().is_pax_global_extensions() {
+            if let Some(extensions) = entry.pax_extensions()? {
+                for extension in extensions {
+                    let extension = extension?;
+                    if extension.key() == Ok("comment")
+                        && let Ok(value) = extension.value()
+                        && is_sha(value.trim())
+                    {
+                        return Ok(Some(value.trim().to_string()));
+                    }
+                }
+            }
             continue;
         }
 
-        if file_type.is_dir() {
-            fs::create_dir_all(&dst_path)?;
-            copy_dir_contents(&src_path, &dst_path)?;
-        } else {
-            fs::copy(&src_path, &dst_path)?;
+        let path = entry.path()?;
+        let root = path.components().next();
+        let commit = root
+            .and_then(|c| c.as_os_str().to_str())
+            .and_then(|root| root.rsplit_once('-'))
+            .map(|(_, suffix)| suffix)
+            .filter(|suffix| is_sha(suffix))
+            .map(String::from);
+        return Ok(commit);
+    }
+
+    Ok(None)
+}
+
+/// Read the jump-start.yaml file of the starter in `starter_dir`, if it has one
+fn read_starter_config(starter_dir: &Path) -> Result<Option<StarterConfig>> {
+    let starter_config_path = starter_dir.join("jump-start.yaml");
+    if !starter_config_path.exists() {
+        return Ok(None);
+    }
+
+    let file_content = fs::read_to_string(&starter_config_path)?;
+    let starter_config = file_content
+        .parse::<StarterConfig>()
+        .with_context(|| format!("Could not parse yaml: {:?}", starter_config_path))?;
+    Ok(Some(starter_config))
+}
+
+/// How deeply `clone` actions in degit.json may nest before giving up on a cycle
+const MAX_CLONE_DEPTH: usize = 8;
+
+/// List the files of the starter in `starter_dir` as they would be written, rendering templates
+/// with `vars` if the starter declares variables and running the actions in its degit.json
+pub fn plan_starter(
+    config: &Config,
+    starter_dir: &Path,
+    vars: &[(String, String)],
+    options: &UseOptions,
+) -> Result<Vec<PlannedFile>> {
+    plan_starter_at_depth(config, starter_dir, vars, options, 0)
+}
+
+fn plan_starter_at_depth(
+    config: &Config,
+    starter_dir: &Path,
+    vars: &[(String, String)],
+    options: &UseOptions,
+    depth: usize,
+) -> Result<Vec<PlannedFile>> {
+    let starter_config = read_starter_config(starter_dir)?.unwrap_or_default();
+    let declared = starter_config.variables.clone().unwrap_or_default();
+
+    // Only starters that opt in with `variables` are rendered, so that files containing
+    // literal "{{" (e.g. GitHub workflows) are left alone
+    let renderer = if declared.is_empty() && vars.is_empty() {
+        None
+    } else {
+        let variables = resolve_variables(&declared, vars)?;
+        debug!("Template variables {:?}", variables);
+        Some(TemplateRenderer::new(variables))
+    };
+
+    let filter = StarterFilter::new(starter_dir, Some(&starter_config))?;
+    let mut files = plan_dir(starter_dir, renderer.as_ref(), &filter)?;
+    select_features(&starter_config, starter_dir, &mut files, &options.with)?;
+    for action in degit::read_actions(starter_dir)? {
+        debug!("Running degit action {:?}", action);
+        match action {
+            DegitAction::Clone { src } => {
+                if depth >= MAX_CLONE_DEPTH {
+                    anyhow::bail!(
+                        "degit.json clone actions nest more than {} deep. Is there a cycle?",
+                        MAX_CLONE_DEPTH
+                    );
+                }
+                // --instance only applies to local starters
+                let mut clone_options = UseOptions {
+                    instance: if RemoteStarter::is_remote_identifier(&src) {
+                        None
+                    } else {
+                        options.instance.clone()
+                    },
+                    ..options.clone()
+                };
+                let cloned = fetch_starter(config, &src, &clone_options).with_context(|| {
+                    format!(
+                        "Running clone action for {:?} in {:?}. Sources are starter identifiers as passed to `use`",
+                        src,
+                        starter_dir.join(degit::DEGIT_FILE_NAME)
+                    )
+                })?;
+
+                // Pass on only the variables and features the cloned starter declares
+                let cloned_config = read_starter_config(&cloned.dir)?.unwrap_or_default();
+                let cloned_declared = cloned_config.variables.unwrap_or_default();
+                let cloned_vars: Vec<(String, String)> = vars
+                    .iter()
+                    .filter(|(key, _)| cloned_declared.iter().any(|v| &v.name == key))
+                    .cloned()
+                    .collect();
+                let cloned_features = cloned_config.features.unwrap_or_default();
+                clone_options
+                    .with
+                    .retain(|name| cloned_features.contains_key(name));
+
+                let cloned_files = plan_starter_at_depth(
+                    config,
+                    &cloned.dir,
+                    &cloned_vars,
+                    &clone_options,
+                    depth + 1,
+                )?;
+                degit::merge_files(&mut files, cloned_files);
+            }
+            DegitAction::Remove { files: paths } => degit::remove_files(&mut files, &paths),
         }
     }
+    Ok(files)
+}
 
+/// Leave out the files of features declared in `starter_config` that aren't `selected`
+fn select_features(
+    starter_config: &StarterConfig,
+    starter_dir: &Path,
+    files: &mut Vec<PlannedFile>,
+    selected: &[String],
+) -> Result<()> {
+    let declared = starter_config.features.clone().unwrap_or_default();
+    for name in selected {
+        if !declared.contains_key(name) {
+            let names: Vec<&str> = declared.keys().map(String::as_str).collect();
+            anyhow::bail!(
+                "Starter does not declare feature {:?} (declared: {})",
+                name,
+                if names.is_empty() {
+                    "none".to_string()
+                } else {
+                    names.join(", ")
+                }
+            );
+        }
+    }
+    for (name, feature) in &declared {
+        for pattern in &feature.files {
+            glob::Pattern::new(pattern.trim_end_matches('/'))
+                .with_context(|| format!("Invalid pattern {:?} in feature {:?}", pattern, name))?;
+        }
+    }
+
+    files.retain(|file| {
+        // Features list files as they are named in the starter, before rendering
+        let path = match file.source.strip_prefix(starter_dir) {
+            Ok(path) => path.to_string_lossy().replace('\\', "/"),
+            Err(_) => file.key(),
+        };
+        let features = starter_config.features_for(&path);
+        let keep = features.is_empty() || features.iter().any(|f| selected.iter().any(|s| s == f));
+        if !keep {
+            debug!("Leaving out {} (features: {})", path, features.join(", "));
+        }
+        keep
+    });
     Ok(())
 }
 
-/// Download a starter from GitHub, storing it in `dest`.
+/// Copy the starter in `starter_dir` to `final_dest`, along with `manifest` if given.
 ///
-/// `mode` should be "tar" or "git" but "git" is not yet implemented.
-fn clone_remote_starter(starter: RemoteStarter, dest: Option<&str>, mode: &str) -> Result<PathBuf> {
-    if mode == "tar" {
-        let project_dirs = ProjectDirs::from("", "", "jump-start")
-            .unwrap_or_else(|| panic!("Could not find OS project directory"));
-
-        let cache_dir = project_dirs
-            .cache_dir()
-            .join("github")
-            .join(&starter.github_username)
-            .join(&starter.github_repo);
-
-        let tar_url = format!(
-            "https://www.github.com/{}/{}/archive/HEAD.tar.gz",
-            starter.github_username, starter.github_repo
-        );
-        let tar_path = download_tar(&tar_url, &cache_dir)
-            .with_context(|| format!("Downloading tar {}", tar_url))?;
-        let subdir = format!("{}/{}", starter.group, starter.name);
-        let cache_dest = cache_dir.join(&subdir);
+/// The manifest is planned like the starter's files, so an existing .jump-start.json, e.g. from
+/// another starter, is a conflict resolved by `options.on_conflict`.
+fn copy_starter(
+    config: &Config,
+    starter_dir: &Path,
+    final_dest: &Path,
+    manifest: Option<Manifest>,
+    options: &UseOptions,
+) -> Result<()> {
+    let mut files = plan_starter(config, starter_dir, &options.vars, options)?;
+    let starter_files = files.len();
+    // Using the same starter again keeps the existing manifest's creation time, so that it is
+    // left alone if nothing else changed, like the starter's unchanged files
+    let previous = Manifest::load(final_dest)
+        .ok()
+        .flatten()
+        .filter(|m| manifest.as_ref().is_some_and(|n| n.starter == m.starter));
+    let manifest = match manifest {
+        Some(manifest) => {
+            let manifest = Manifest {
+                created_at: previous
+                    .as_ref()
+                    .map_or(manifest.created_at, |m| m.created_at),
+                files: hash_files(&files)?,
+                ..manifest
+            };
+            files.push(PlannedFile {
+                source: PathBuf::new(),
+                path: PathBuf::from(MANIFEST_FILE_NAME),
+                rendered: Some(manifest.to_json()?),
+            });
+            Some(manifest)
+        }
+        None => None,
+    };
+    if options.dry_run {
+        return print_plan(&files, final_dest, options);
+    }
 
-        extract_tar_subdir(&tar_path, &subdir, &cache_dest)
-            .with_context(|| format!("Extracting tar {} into {:?}", tar_url, cache_dest))?;
-        debug!(
-            "Extracted {:?} with subdir {:?} to {:?}",
-            tar_path, subdir, cache_dest
-        );
+    let copied = copy_files(&files, final_dest, options.on_conflict)?;
+    // The manifest is planned last, so it is copied last unless it was skipped
+    let wrote_manifest = manifest.is_some()
+        && copied
+            .last()
+            .zip(files.last())
+            .is_some_and(|(copied, planned)| std::ptr::eq(*copied, planned));
+    let copied = &copied[..copied.len() - usize::from(wrote_manifest)];
+    // Skipped files don't match the starter, so they keep the hashes recorded by the previous
+    // use of the same starter, if any
+    if let Some(manifest) = manifest
+        && wrote_manifest
+        && copied.len() < starter_files
+    {
+        let mut files = previous.map(|m| m.files).unwrap_or_default();
+        files.retain(|key, _| manifest.files.contains_key(key));
+        files.extend(hash_files(copied.iter().copied())?);
+        Manifest { files, ..manifest }.save(final_dest)?;
+    }
+    Ok(())
+}
 
-        let final_dest = get_final_dest(&cache_dest, dest)?;
+/// Hash the contents `files` will have in the destination
+fn hash_files<'a>(files: impl IntoIterator<Item = &'a PlannedFile>) -> Result<FileHashes> {
+    files
+        .into_iter()
+        .map(|f| Ok((f.key(), hash_contents(&f.contents()?))))
+        .collect()
+}
 
-        copy_dir_contents(&cache_dest, &final_dest)
-            .with_context(|| format!("Copying dir contents"))?;
-        Ok(final_dest)
-    } else {
-        anyhow::bail!("Not implemented")
-    }
+/// Download a starter from its host (GitHub by default), returning the directory holding its
+/// files and the commit it resolved to, if known.
+///
+/// The repository is cached under the cache directory, either as a tarball or as a git clone
+/// depending on `options.mode`.
+pub fn fetch_remote_starter(
+    config: &Config,
+    starter: &RemoteStarter,
+    options: &UseOptions,
+) -> Result<(PathBuf, Option<String>)> {
+    let host = resolve_host(config, starter.host.as_deref())?;
+    let cache_dir = get_cache_dir(config)
+        .join(&host.name)
+        .join(&starter.github_username)
+        .join(starter.cache_dir_name());
+    let subdir = format!("{}/{}", starter.group, starter.name);
+
+    let (cache_dest, commit) = match options.mode {
+        CloneMode::Tar => {
+            let tar_url = host.archive_url(
+                &starter.github_username,
+                &starter.github_repo,
+                starter.archive_ref(),
+            );
+            let policy = if starter.is_pinned_to_commit() {
+                // The contents of a commit never change
+                CachePolicy {
+                    ttl: Duration::MAX,
+                    refresh: false,
+                }
+            } else {
+                CachePolicy {
+                    ttl: config
+                        .cache_ttl
+                        .map(Duration::from_secs)
+                        .unwrap_or(DEFAULT_CACHE_TTL),
+                    refresh: options.refresh,
+                }
+            };
+            let tar_path = download_tar(&tar_url, &cache_dir, &policy, host.auth_header()?)
+                .with_context(|| {
+                    format!(
+                        "Downloading tar {} (tokens for host {:?} are read from \"token\" in its config or ${})",
+                        tar_url,
+                        host.name,
+                        host.token_env_var()
+                    )
+                })?;
+            let cache_dest = cache_dir.join(&subdir);
+
+            // Clear out files from a previous extraction, which may be from an older tarball
+            if cache_dest.exists() {
+                fs::remove_dir_all(&cache_dest)?;
+            }
+
+            extract_tar_subdir(&tar_path, &subdir, &cache_dest)
+                .with_context(|| format!("Extracting tar {} into {:?}", tar_url, cache_dest))?;
+            debug!(
+                "Extracted {:?} with subdir {:?} to {:?}",
+                tar_path, subdir, cache_dest
+            );
+            (cache_dest, read_tar_commit(&tar_path)?)
+        }
+        CloneMode::Git => {
+            let git_url = host.clone_url(&starter.github_username, &starter.github_repo);
+            let clone_dir = cache_dir.join(".git-clone");
+
+            git::clone_subdir(&git_url, &clone_dir, &subdir, starter.archive_ref())
+                .with_context(|| format!("Cloning {} into {:?}", git_url, clone_dir))?;
+            let commit = git::head_commit(&clone_dir)?;
+            debug!("Cloned {} at {} into {:?}", git_url, commit, clone_dir);
+
+            let cache_dest = clone_dir.join(&subdir);
+            if !cache_dest.is_dir() {
+                anyhow::bail!("Subdirectory '{}' not found in {}", subdir, git_url);
+            }
+            (cache_dest, Some(commit))
+        }
+    };
+
+    Ok((cache_dest, commit))
 }
 
 /// Get the final destination for the starter according to these rules:
@@ -236,17 +1062,42 @@ fn get_final_dest(starter_path_full: &PathBuf, dest: Option<&str>) -> Result<Pat
     Ok(final_dest)
 }
 
-/// Copy a starter into `dest`.
-fn clone_local_starter(
-    instance: &JumpStartInstance,
-    starter: LocalStarter,
-    dest: Option<&str>,
-) -> Result<PathBuf> {
-    let starter_path_full = instance.path.join(starter.path);
-    let final_dest = get_final_dest(&starter_path_full, dest)?;
+/// Find the instance and starter for a local identifier, either "group/name" or
+/// "instance/group/name". Groups may be nested, so a first segment naming a configured
+/// instance is taken as the instance. `instance_name` (from `--instance`) replaces the default
+/// instance.
+fn resolve_local_starter<'a>(
+    config: &'a Config,
+    identifier: &str,
+    instance_name: Option<&str>,
+) -> Result<(&'a JumpStartInstance, LocalStarter)> {
+    let (prefix, path) = match identifier.split_once('/') {
+        Some((prefix, rest))
+            if rest.contains('/') && config.instances.iter().any(|i| i.name == prefix) =>
+        {
+            (Some(prefix), rest)
+        }
+        _ => (None, identifier),
+    };
 
-    copy_dir_contents(&starter_path_full, &final_dest)?;
-    Ok(final_dest)
+    let instance = match (prefix, instance_name) {
+        (Some(prefix), Some(flag)) if prefix != flag => anyhow::bail!(
+            "Starter {:?} names instance {:?}, but --instance is {:?}",
+            identifier,
+            prefix,
+            flag
+        ),
+        (Some(name), _) | (None, Some(name)) => get_instance(config, name)?,
+        (None, None) => get_default_instance(config),
+    };
+
+    let starter = LocalStarter::from_path(path).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Invalid starter identifier {:?}, expected group/name or instance/group/name",
+            identifier
+        )
+    })?;
+    Ok((instance, starter))
 }
 
 #[cfg(test)]
@@ -257,6 +1108,93 @@ mod tests {
     use tar::Builder;
     use tempfile::{TempDir, tempdir};
 
+    #[cfg(unix)]
+    #[test]
+    fn test_open_with() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempdir()?;
+        let editor = temp_dir.path().join("editor");
+        fs::write(
+            &editor,
+            format!(
+                "#!/bin/sh\necho \"$@\" > {:?}\n",
+                temp_dir.path().join("args.txt")
+            ),
+        )?;
+        fs::set_permissions(&editor, fs::Permissions::from_mode(0o755))?;
+
+        let main_file = temp_dir.path().join("index.js");
+        open_with(&format!("{} --wait", editor.display()), &main_file)?;
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("args.txt"))?,
+            format!("--wait {}\n", main_file.display())
+        );
+
+        let err = open_with("false", &main_file).unwrap_err();
+        assert!(err.to_string().contains("exited with"), "{}", err);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_trust() -> Result<()> {
+        let config = Config {
+            trusted_sources: vec!["kevinschaul".to_string()],
+            ..Default::default()
+        };
+        let host = resolve_host(&config, None)?;
+        let trusted = RemoteStarter::from_path("@kevinschaul/react-d3/Chart").unwrap();
+        let untrusted = RemoteStarter::from_path("@kevinschual/react-d3/Chart@v1").unwrap();
+        let options = UseOptions::default();
+
+        check_trust(&config, &trusted, &host, &options, &[], None)?;
+
+        let err = check_trust(&config, &untrusted, &host, &options, &[], None).unwrap_err();
+        let message = err.to_string();
+        assert!(
+            message.contains("github/kevinschual/jump-start is not a trusted source"),
+            "{}",
+            message
+        );
+        // The message shows where the starter would be downloaded from
+        assert!(
+            message.contains(&host.archive_url("kevinschual", "jump-start", "v1")),
+            "{}",
+            message
+        );
+        assert!(message.contains("--trust"), "{}", message);
+
+        let mut yes = io::Cursor::new("maybe\ny\n");
+        check_trust(&config, &untrusted, &host, &options, &[], Some(&mut yes))?;
+        let mut no = io::Cursor::new("\n");
+        assert!(check_trust(&config, &untrusted, &host, &options, &[], Some(&mut no)).is_err());
+
+        let options = UseOptions {
+            trust: true,
+            ..Default::default()
+        };
+        check_trust(&config, &untrusted, &host, &options, &[], None)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_trust_warning() {
+        let url = "https://github.com/someone/repo/archive/HEAD.tar.gz";
+        let warning = trust_warning("github/someone/repo", url, &[]);
+        assert!(warning.contains(url), "{}", warning);
+        assert!(!warning.contains("hooks"), "{}", warning);
+
+        let hooks = vec!["npm install".to_string(), "git init".to_string()];
+        let warning = trust_warning("github/someone/repo", url, &hooks);
+        assert!(
+            warning.ends_with("post_use hooks in the destination:\n  npm install\n  git init"),
+            "{}",
+            warning
+        );
+    }
+
     fn create_test_archive(
         root_dir: &str,
         _target_subdir: &str,
@@ -416,32 +1354,364 @@ mod tests {
     }
 
     #[test]
-    fn test_copy_dir_contents() -> Result<()> {
+    fn test_read_tar_commit() -> Result<()> {
+        let sha = "b3c8d936025b11b9a57cfac99e0decb9f908042e";
+
+        // From the root directory name
+        let (_temp, archive_path) = create_test_archive(
+            &format!("jump-start-{}", sha),
+            "",
+            vec![("group/starter/file.txt".to_string(), "contents")],
+        )?;
+        assert_eq!(read_tar_commit(&archive_path)?.as_deref(), Some(sha));
+
+        // Tags have no commit in their root directory name
+        let (_temp, archive_path) = create_test_archive(
+            "jump-start-1.2",
+            "",
+            vec![("group/starter/file.txt".to_string(), "contents")],
+        )?;
+        assert_eq!(read_tar_commit(&archive_path)?, None);
+
+        // From the pax global header written by `git archive`
         let temp_dir = tempdir()?;
-        let src_dir = temp_dir.path().join("src");
-        let dest_dir = temp_dir.path().join("dest");
-
-        // Create source directory with some files
-        fs::create_dir_all(src_dir.join("nested"))?;
-        fs::write(src_dir.join("file1.txt"), "test content 1")?;
-        fs::write(src_dir.join("nested/file2.txt"), "test content 2")?;
-        // Add configuration files - these should be excluded
-        fs::write(src_dir.join("jump-start.yaml"), "name: test-starter\ndescription: Test")?;
-        fs::write(src_dir.join("degit.json"), "{\"action\": \"remove\"}")?;
-
-        copy_dir_contents(&src_dir, &dest_dir)?;
-
-        // Verify files were copied correctly
-        assert!(dest_dir.join("file1.txt").exists());
-        assert!(dest_dir.join("nested/file2.txt").exists());
-
-        // Verify configuration files were NOT copied
-        assert!(!dest_dir.join("jump-start.yaml").exists());
-        assert!(!dest_dir.join("degit.json").exists());
-
-        // Check file contents
-        let file1_content = fs::read_to_string(dest_dir.join("file1.txt"))?;
-        assert_eq!(file1_content, "test content 1");
+        let archive_path = temp_dir.path().join("tag.tar.gz");
+        let mut builder = Builder::new(GzEncoder::new(
+            File::create(&archive_path)?,
+            Compression::default(),
+        ));
+        let record = format!(" comment={}\n", sha);
+        let record = format!("{}{}", record.len() + 2, record);
+        let mut header = tar::Header::new_ustar();
+        header.set_entry_type(tar::EntryType::XGlobalHeader);
+        header.set_size(record.len() as u64);
+        header.set_cksum();
+        builder.append_data(&mut header, "pax_global_header", record.as_bytes())?;
+        let mut header = tar::Header::new_gnu();
+        header.set_size(8);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(
+            &mut header,
+            "jump-start-1.2/group/starter/file.txt",
+            "contents".as_bytes(),
+        )?;
+        builder.into_inner()?.finish()?;
+        assert_eq!(read_tar_commit(&archive_path)?.as_deref(), Some(sha));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_download_and_extract_per_host() -> Result<()> {
+        use crate::host::{Host, HostKind};
+
+        let sha = "b3c8d936025b11b9a57cfac99e0decb9f908042e";
+        let cases = [
+            (
+                HostKind::Github,
+                "/owner/repo/archive/HEAD.tar.gz".to_string(),
+                format!("repo-{}", sha),
+                Some(sha),
+            ),
+            (
+                HostKind::Gitlab,
+                "/api/v4/projects/owner%2Frepo/repository/archive.tar.gz".to_string(),
+                format!("repo-main-{}", sha),
+                Some(sha),
+            ),
+            (
+                HostKind::Gitea,
+                "/api/v1/repos/owner/repo/archive/HEAD.tar.gz".to_string(),
+                "repo".to_string(),
+                None,
+            ),
+            (
+                HostKind::Bitbucket,
+                "/owner/repo/get/HEAD.tar.gz".to_string(),
+                format!("owner-repo-{}", &sha[..12]),
+                None,
+            ),
+        ];
+
+        for (kind, path, root_dir, expected_commit) in cases {
+            let mut server = mockito::Server::new();
+            let (_archive_temp, archive_path) = create_test_archive(
+                &root_dir,
+                "",
+                vec![
+                    ("group/starter/file1.txt".to_string(), "file 1"),
+                    ("group/other/file2.txt".to_string(), "file 2"),
+                ],
+            )?;
+            let mock = server
+                .mock("GET", path.as_str())
+                .with_status(200)
+                .with_body(fs::read(&archive_path)?)
+                .create();
+            let host = Host {
+                name: "test".to_string(),
+                kind,
+                url: server.url(),
+                git_url: None,
+                default: None,
+                token: None,
+                token_env: None,
+            };
+
+            let cache_dir = tempdir()?;
+            let tar_url = host.archive_url("owner", "repo", "HEAD");
+            let tar_path = download_tar(&tar_url, cache_dir.path(), &CachePolicy::default(), None)?;
+            mock.assert();
+
+            let extract_path = cache_dir.path().join("group/starter");
+            extract_tar_subdir(&tar_path, "group/starter", &extract_path)?;
+            assert_eq!(
+                fs::read_to_string(extract_path.join("file1.txt"))?,
+                "file 1",
+                "{:?}",
+                kind
+            );
+            assert!(!extract_path.join("file2.txt").exists(), "{:?}", kind);
+            assert_eq!(
+                read_tar_commit(&tar_path)?.as_deref(),
+                expected_commit,
+                "{:?}",
+                kind
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_download_tar_uses_fresh_cache() -> Result<()> {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("GET", "/archive/HEAD.tar.gz")
+            .with_status(200)
+            .with_header("etag", "\"v1\"")
+            .with_body("tarball v1")
+            .expect(1)
+            .create();
+        let url = format!("{}/archive/HEAD.tar.gz", server.url());
+        let temp_dir = tempdir()?;
+        let policy = CachePolicy::default();
+
+        let tar_path = download_tar(&url, temp_dir.path(), &policy, None)?;
+        assert_eq!(fs::read_to_string(&tar_path)?, "tarball v1");
+        let metadata = CacheMetadata::load(&tar_path).unwrap();
+        assert_eq!(metadata.etag.as_deref(), Some("\"v1\""));
+
+        // The second download is served from the cache without a request
+        let tar_path = download_tar(&url, temp_dir.path(), &policy, None)?;
+        assert_eq!(fs::read_to_string(&tar_path)?, "tarball v1");
+        mock.assert();
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_download_tar_revalidates_stale_cache() -> Result<()> {
+        let mut server = mockito::Server::new();
+        let url = format!("{}/archive/HEAD.tar.gz", server.url());
+        let temp_dir = tempdir()?;
+        let tar_path = temp_dir.path().join("HEAD.tar.gz");
+        fs::write(&tar_path, "tarball v1")?;
+        CacheMetadata {
+            url: url.clone(),
+            etag: Some("\"v1\"".to_string()),
+            last_modified: Some("Wed, 01 Jan 2025 00:00:00 GMT".to_string()),
+            fetched_at: 0,
+        }
+        .save(&tar_path)?;
+
+        let not_modified = server
+            .mock("GET", "/archive/HEAD.tar.gz")
+            .match_header("if-none-match", "\"v1\"")
+            .match_header("if-modified-since", "Wed, 01 Jan 2025 00:00:00 GMT")
+            .with_status(304)
+            .create();
+
+        download_tar(&url, temp_dir.path(), &CachePolicy::default(), None)?;
+        not_modified.assert();
+        assert_eq!(fs::read_to_string(&tar_path)?, "tarball v1");
+        assert!(CacheMetadata::load(&tar_path).unwrap().fetched_at > 0);
+
+        // With --refresh and a changed remote, the new tarball replaces the cached one
+        not_modified.remove();
+        let changed = server
+            .mock("GET", "/archive/HEAD.tar.gz")
+            .with_status(200)
+            .with_header("etag", "\"v2\"")
+            .with_body("tarball v2")
+            .create();
+        let policy = CachePolicy {
+            refresh: true,
+            ..Default::default()
+        };
+
+        download_tar(&url, temp_dir.path(), &policy, None)?;
+        changed.assert();
+        assert_eq!(fs::read_to_string(&tar_path)?, "tarball v2");
+        assert_eq!(
+            CacheMetadata::load(&tar_path).unwrap().etag.as_deref(),
+            Some("\"v2\"")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_download_tar_offline_uses_stale_cache() -> Result<()> {
+        // Nothing listens on port 1, so the request fails to connect
+        let url = "http://127.0.0.1:1/archive/HEAD.tar.gz";
+        let temp_dir = tempdir()?;
+        let tar_path = temp_dir.path().join("HEAD.tar.gz");
+        let policy = CachePolicy {
+            refresh: true,
+            ..Default::default()
+        };
+
+        assert!(download_tar(url, temp_dir.path(), &policy, None).is_err());
+
+        fs::write(&tar_path, "tarball v1")?;
+        CacheMetadata {
+            url: url.to_string(),
+            fetched_at: 0,
+            ..Default::default()
+        }
+        .save(&tar_path)?;
+
+        let result = download_tar(url, temp_dir.path(), &policy, None)?;
+        assert_eq!(fs::read_to_string(result)?, "tarball v1");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_download_tar_not_found() -> Result<()> {
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/archive/HEAD.tar.gz")
+            .with_status(404)
+            .create();
+        let url = format!("{}/archive/HEAD.tar.gz", server.url());
+        let temp_dir = tempdir()?;
+
+        let result = download_tar(&url, temp_dir.path(), &CachePolicy::default(), None);
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("Not found (404"), "{}", err);
+        assert!(err.to_string().contains("set a token"), "{}", err);
+        assert!(!temp_dir.path().join("HEAD.tar.gz").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_download_tar_with_token() -> Result<()> {
+        use crate::host::{Host, HostKind, Token};
+
+        let mut server = mockito::Server::new();
+        let mut host = Host {
+            name: "test".to_string(),
+            kind: HostKind::Gitlab,
+            url: server.url(),
+            git_url: None,
+            default: None,
+            token: Some(Token::new("glpat-secret")),
+            token_env: None,
+        };
+        let tar_url = host.archive_url("owner", "private", "HEAD");
+        let path = "/api/v4/projects/owner%2Fprivate/repository/archive.tar.gz";
+
+        let authorized = server
+            .mock("GET", path)
+            .match_header("private-token", "glpat-secret")
+            .with_status(200)
+            .with_body("tarball")
+            .create();
+        let temp_dir = tempdir()?;
+        let tar_path = download_tar(
+            &tar_url,
+            temp_dir.path(),
+            &CachePolicy::default(),
+            host.auth_header()?,
+        )?;
+        authorized.assert();
+        assert_eq!(fs::read_to_string(tar_path)?, "tarball");
+
+        // A rejected token is reported as an access problem rather than a missing repository
+        authorized.remove();
+        server.mock("GET", path).with_status(401).create();
+        host.token = Some(Token::new("expired"));
+        let err = download_tar(
+            &tar_url,
+            tempdir()?.path(),
+            &CachePolicy::default(),
+            host.auth_header()?,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Access denied (401"), "{}", err);
+        assert!(!format!("{:?}", err).contains("expired"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_local_starter() -> Result<()> {
+        let instance = |name: &str, path: &str, default| JumpStartInstance {
+            name: name.to_string(),
+            path: PathBuf::from(path),
+            default,
+        };
+        let mut config = Config {
+            instances: vec![
+                instance("personal", "/personal", Some(true)),
+                instance("work", "/work", None),
+            ],
+            ..Default::default()
+        };
+
+        let (found, starter) = resolve_local_starter(&config, "group/name", None)?;
+        assert_eq!(found.name, "personal");
+        assert_eq!(starter.path, "group/name");
+
+        let (found, starter) = resolve_local_starter(&config, "work/group/name", None)?;
+        assert_eq!(found.name, "work");
+        assert_eq!(starter.path, "group/name");
+
+        let (found, _) = resolve_local_starter(&config, "group/name", Some("work"))?;
+        assert_eq!(found.name, "work");
+        assert!(resolve_local_starter(&config, "work/group/name", Some("work")).is_ok());
+
+        let err = resolve_local_starter(&config, "work/group/name", Some("personal")).unwrap_err();
+        assert!(err.to_string().contains("--instance"), "{}", err);
+
+        // Unknown instances are nested groups
+        let (found, starter) = resolve_local_starter(&config, "frontend/react/name", None)?;
+        assert_eq!(found.name, "personal");
+        assert_eq!(starter.group, "frontend/react");
+
+        let (found, starter) = resolve_local_starter(&config, "work/a/b/name", None)?;
+        assert_eq!(found.name, "work");
+        assert_eq!(starter.group, "a/b");
+
+        let err = resolve_local_starter(&config, "group/name", Some("nope")).unwrap_err();
+        assert!(
+            err.to_string().contains("Unknown instance \"nope\""),
+            "{}",
+            err
+        );
+        assert!(err.to_string().contains("personal, work"), "{}", err);
+
+        assert!(resolve_local_starter(&config, "name", None).is_err());
+        assert!(resolve_local_starter(&config, "a//name", None).is_err());
+
+        config.instances.push(instance("work", "/other-work", None));
+        let err = resolve_local_starter(&config, "work/group/name", None).unwrap_err();
+        assert!(err.to_string().contains("ambiguous"), "{}", err);
+
         Ok(())
     }
 
@@ -0,0 +1,355 @@
+use crate::config::resolve_instance_path;
+use crate::copy::plan_dir;
+use crate::degit::read_actions;
+use crate::filter::{StarterFilter, walk_instance};
+use crate::schema::check_starter_config;
+use crate::starter::{StarterConfig, find_collisions};
+use crate::{Config, LocalStarter, LocalStarterGroupLookup};
+use anyhow::Result;
+use log::{debug, info};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// How serious an issue is. Errors make `validate` fail, warnings only do with `--strict`
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A problem found in an instance
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Issue {
+    pub severity: Severity,
+    /// Identifier of the starter the issue is in, if any
+    pub starter: Option<String>,
+    /// The file or directory the issue is in
+    pub path: PathBuf,
+    /// Line of `path` the issue is on, starting at 1, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+/// Check every starter in the instance at `instance_dir`.
+///
+/// Starters are checked for jump-start.yaml files that don't parse or have unknown keys, a
+/// `mainFile` that isn't in the starter, a `defaultDir` outside the current directory, a
+/// missing description, no files to copy, and collisions with other starters.
+pub fn validate_instance(instance_dir: &Path) -> Result<Vec<Issue>> {
+    let mut issues = Vec::new();
+    let mut groups: LocalStarterGroupLookup = HashMap::new();
+
+    for entry in walk_instance(instance_dir) {
+        // Keep going past unreadable directories and ignore files, so the rest is still checked
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                let (path, message) = split_walk_error(&e);
+                issues.push(Issue {
+                    severity: Severity::Error,
+                    starter: None,
+                    path: path.unwrap_or(instance_dir).to_path_buf(),
+                    line: None,
+                    column: None,
+                    message,
+                });
+                continue;
+            }
+        };
+        if entry.file_name() != "jump-start.yaml" || !entry.file_type().is_some_and(|t| t.is_file())
+        {
+            continue;
+        }
+        let config_path = entry.path();
+        let starter_dir = config_path.parent().unwrap();
+        debug!("Validating {:?}", config_path);
+
+        let Some(starter) = LocalStarter::from_dir(instance_dir, starter_dir) else {
+            issues.push(Issue {
+                severity: Severity::Error,
+                starter: None,
+                path: config_path.to_path_buf(),
+                line: None,
+                column: None,
+                message: "Starters must be inside a group directory".to_string(),
+            });
+            continue;
+        };
+        let contents = match fs::read_to_string(config_path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                issues.push(Issue {
+                    severity: Severity::Error,
+                    starter: Some(starter.path.clone()),
+                    path: config_path.to_path_buf(),
+                    line: None,
+                    column: None,
+                    message: format!("Couldn't read jump-start.yaml: {}", e),
+                });
+                groups
+                    .entry(starter.group.clone())
+                    .or_default()
+                    .push(starter);
+                continue;
+            }
+        };
+        let check = check_starter_config(&contents);
+        let schema_errors = check.errors;
+        for e in &schema_errors {
+            issues.push(Issue {
+                severity: Severity::Error,
+                starter: Some(starter.path.clone()),
+                path: config_path.to_path_buf(),
+                line: Some(e.line),
+                column: Some(e.column),
+                message: format!("Invalid jump-start.yaml at {}", e),
+            });
+        }
+        // The published schema rejects unknown keys, so editors flag them as errors too
+        for e in check.unknown_keys {
+            issues.push(Issue {
+                severity: Severity::Error,
+                starter: Some(starter.path.clone()),
+                path: config_path.to_path_buf(),
+                line: Some(e.line),
+                column: Some(e.column),
+                message: format!("{} in jump-start.yaml", e.message),
+            });
+        }
+
+        let mut issue = |severity, message: String| {
+            issues.push(Issue {
+                severity,
+                starter: Some(starter.path.clone()),
+                path: config_path.to_path_buf(),
+                line: None,
+                column: None,
+                message,
+            })
+        };
+
+        let config = match contents.parse::<StarterConfig>() {
+            Ok(config) if schema_errors.is_empty() => config,
+            parsed => {
+                if let Err(e) = parsed
+                    && schema_errors.is_empty()
+                {
+                    issue(Severity::Error, format!("Invalid jump-start.yaml: {}", e));
+                }
+                groups
+                    .entry(starter.group.clone())
+                    .or_default()
+                    .push(starter);
+                continue;
+            }
+        };
+
+        if config
+            .description
+            .as_deref()
+            .is_none_or(|d| d.trim().is_empty())
+        {
+            issue(Severity::Warning, "Missing description".to_string());
+        }
+
+        if let Some(default_dir) = &config.default_dir
+            && !is_relative_and_inside(default_dir)
+        {
+            issue(
+                Severity::Error,
+                format!(
+                    "defaultDir {:?} must be a relative path that stays inside the current directory",
+                    default_dir
+                ),
+            );
+        }
+
+        let filter = match StarterFilter::new(starter_dir, Some(&config)) {
+            Ok(filter) => Some(filter),
+            Err(e) => {
+                issue(Severity::Error, format!("{:#}", e));
+                None
+            }
+        };
+
+        if let Some(main_file) = &config.main_file {
+            let main_path = Path::new(main_file);
+            if !is_relative_and_inside(main_path) || !starter_dir.join(main_path).is_file() {
+                issue(
+                    Severity::Error,
+                    format!("mainFile {:?} is not a file in the starter", main_file),
+                );
+            } else if filter
+                .as_ref()
+                .is_some_and(|f| !f.is_included(main_path, false))
+            {
+                issue(
+                    Severity::Error,
+                    format!(
+                        "mainFile {:?} is left out by the starter's include/exclude patterns",
+                        main_file
+                    ),
+                );
+            }
+        }
+
+        // Starters whose files all come from degit.json clone actions have none of their own
+        match read_actions(starter_dir) {
+            Ok(actions) => {
+                if actions.is_empty()
+                    && let Some(filter) = &filter
+                {
+                    match plan_dir(starter_dir, None, filter) {
+                        Ok(files) if files.is_empty() => {
+                            issue(Severity::Error, "No files to copy".to_string())
+                        }
+                        Ok(_) => {}
+                        Err(e) => issue(
+                            Severity::Error,
+                            format!("Couldn't list the starter's files: {:#}", e),
+                        ),
+                    }
+                }
+            }
+            Err(e) => issue(Severity::Error, format!("{:#}", e)),
+        }
+
+        groups
+            .entry(starter.group.clone())
+            .or_default()
+            .push(starter);
+    }
+
+    for collision in find_collisions(instance_dir, &groups) {
+        issues.push(Issue {
+            severity: Severity::Error,
+            starter: None,
+            path: instance_dir.to_path_buf(),
+            line: None,
+            column: None,
+            message: collision,
+        });
+    }
+
+    Ok(issues)
+}
+
+/// The path a walk error is about, if it names one, and the error without it
+fn split_walk_error(err: &ignore::Error) -> (Option<&Path>, String) {
+    match err {
+        ignore::Error::WithPath { path, err } => (Some(path), split_walk_error(err).1),
+        ignore::Error::WithDepth { err, .. } => split_walk_error(err),
+        ignore::Error::Loop { child, .. } => (Some(child), err.to_string()),
+        _ => (None, err.to_string()),
+    }
+}
+
+/// Whether `path` is relative and doesn't climb out of the directory it is relative to
+///
+/// ```
+/// use jump_start::commands::validate::is_relative_and_inside;
+/// use std::path::Path;
+///
+/// assert!(is_relative_and_inside(Path::new("./src/main.rs")));
+/// assert!(!is_relative_and_inside(Path::new("/tmp/project")));
+/// assert!(!is_relative_and_inside(Path::new("../project")));
+/// assert!(!is_relative_and_inside(Path::new("src/../../project")));
+/// ```
+pub fn is_relative_and_inside(path: &Path) -> bool {
+    let mut depth = 0usize;
+    for component in path.components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir => match depth.checked_sub(1) {
+                Some(d) => depth = d,
+                None => return false,
+            },
+            Component::RootDir | Component::Prefix(_) => return false,
+        }
+    }
+    true
+}
+
+/// Check the starters in an instance, printing the issues found. Fails if there are errors, or
+/// warnings when `config.strict` is set, so it can be used in CI
+pub fn validate(config: Config, instance_path: Option<&str>, json: bool) -> Result<()> {
+    let path = resolve_instance_path(&config, instance_path);
+    let issues = validate_instance(&path)?;
+
+    let count = |severity| issues.iter().filter(|i| i.severity == severity).count();
+    let (errors, warnings) = (count(Severity::Error), count(Severity::Warning));
+
+    if json {
+        let report = json!({
+            "instance": path,
+            "errors": errors,
+            "warnings": warnings,
+            "issues": issues,
+        });
+        println!("{}", serde_json::to_string(&report)?);
+    } else {
+        for issue in &issues {
+            match &issue.starter {
+                Some(starter) => println!("{}: {}: {}", issue.severity, starter, issue.message),
+                None => println!(
+                    "{}: {}: {}",
+                    issue.severity,
+                    issue.path.display(),
+                    issue.message
+                ),
+            }
+        }
+        info!("{} errors, {} warnings in {:?}", errors, warnings, path);
+    }
+
+    if errors > 0 || (config.strict && warnings > 0) {
+        anyhow::bail!("{:?} has {} errors and {} warnings", path, errors, warnings);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_walk_error() {
+        let err = ignore::Error::WithDepth {
+            depth: 2,
+            err: Box::new(ignore::Error::WithPath {
+                path: PathBuf::from("/instance/group"),
+                err: Box::new(ignore::Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::PermissionDenied,
+                    "Permission denied",
+                ))),
+            }),
+        };
+        assert_eq!(
+            split_walk_error(&err),
+            (
+                Some(Path::new("/instance/group")),
+                "Permission denied".to_string()
+            )
+        );
+        let err = ignore::Error::UnrecognizedFileType("x".to_string());
+        assert_eq!(split_walk_error(&err).0, None);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::JumpStartInstance;
+use crate::host::{DEFAULT_HOST, Host, resolve_host};
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -8,6 +9,27 @@ use std::path::PathBuf;
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
     pub instances: Vec<JumpStartInstance>,
+    /// Directory remote starters are cached in. Defaults to the OS cache directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_dir: Option<PathBuf>,
+    /// Base URL used to clone remote starters in git mode. Defaults to "https://github.com/".
+    /// Use "git@github.com:" to clone over SSH.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_base_url: Option<String>,
+    /// Seconds a downloaded remote starter is reused before checking the remote for changes.
+    /// Defaults to one day.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_ttl: Option<u64>,
+    /// Hosts remote starters can be downloaded from, in addition to the built-in ones
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hosts: Vec<Host>,
+    /// Remote starter sources that are used without asking, as "[HOST:]OWNER[/REPO]", e.g.
+    /// "kevinschaul" or "gitlab:team/starters". Local instances are always trusted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trusted_sources: Vec<String>,
+    /// Treat problems found in instances, like colliding starters, as errors instead of warnings
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub strict: bool,
 }
 
 impl ::std::default::Default for Config {
@@ -18,6 +40,12 @@ impl ::std::default::Default for Config {
                 path: PathBuf::new(),
                 default: Some(true),
             }],
+            cache_dir: None,
+            git_base_url: None,
+            cache_ttl: None,
+            hosts: vec![],
+            trusted_sources: vec![],
+            strict: false,
         }
     }
 }
@@ -26,8 +54,58 @@ impl ::std::default::Default for Config {
 pub fn get_config_path() -> PathBuf {
     let project_dirs =
         ProjectDirs::from("", "", "jump-start").expect("Could not find OS project directory");
-    let config_path = project_dirs.config_dir().join("config.json");
-    config_path
+    project_dirs.config_dir().join("config.json")
+}
+
+/// Whether `trusted_sources` lists the repository `owner/repo` on the host named `host`.
+///
+/// Entries without a host prefix apply to the default host. Names are compared
+/// case-insensitively, since hosts treat them that way.
+///
+/// ```
+/// use jump_start::Config;
+/// use jump_start::config::is_trusted_source;
+///
+/// let config = Config {
+///     trusted_sources: vec!["kevinschaul".to_string(), "gitlab:team/starters".to_string()],
+///     ..Default::default()
+/// };
+/// assert!(is_trusted_source(&config, "github", "KevinSchaul", "react-d3"));
+/// assert!(is_trusted_source(&config, "gitlab", "team", "starters"));
+/// assert!(!is_trusted_source(&config, "gitlab", "team", "other"));
+/// assert!(!is_trusted_source(&config, "github", "kevinschual", "react-d3"));
+/// ```
+pub fn is_trusted_source(config: &Config, host: &str, owner: &str, repo: &str) -> bool {
+    let default_host = resolve_host(config, None)
+        .map(|h| h.name)
+        .unwrap_or_else(|_| DEFAULT_HOST.to_string());
+
+    config.trusted_sources.iter().any(|entry| {
+        let (entry_host, source) = match entry.split_once(':') {
+            Some((entry_host, source)) => (entry_host, source),
+            None => (default_host.as_str(), entry.as_str()),
+        };
+        let source = source.trim_start_matches('@').trim_end_matches('/');
+        let (entry_owner, entry_repo) = match source.split_once('/') {
+            Some((entry_owner, entry_repo)) => (entry_owner, Some(entry_repo)),
+            None => (source, None),
+        };
+        entry_host == host
+            && entry_owner.eq_ignore_ascii_case(owner)
+            && entry_repo.is_none_or(|r| r.eq_ignore_ascii_case(repo))
+    })
+}
+
+/// Get the directory remote starters are cached in
+pub fn get_cache_dir(config: &Config) -> PathBuf {
+    match &config.cache_dir {
+        Some(dir) => dir.clone(),
+        None => {
+            let project_dirs = ProjectDirs::from("", "", "jump-start")
+                .expect("Could not find OS project directory");
+            project_dirs.cache_dir().to_path_buf()
+        }
+    }
 }
 
 pub fn load_config(config_path: &PathBuf) -> Result<Config, io::Error> {
@@ -55,6 +133,33 @@ pub fn get_default_instance(config: &Config) -> &JumpStartInstance {
         .unwrap_or(&config.instances[0])
 }
 
+/// Get the instance named `name`
+pub fn get_instance<'a>(config: &'a Config, name: &str) -> anyhow::Result<&'a JumpStartInstance> {
+    let matches: Vec<&JumpStartInstance> =
+        config.instances.iter().filter(|i| i.name == name).collect();
+    match matches.as_slice() {
+        [instance] => Ok(instance),
+        [] => {
+            let names: Vec<&str> = config.instances.iter().map(|i| i.name.as_str()).collect();
+            anyhow::bail!(
+                "Unknown instance {:?}. Configured instances: {}",
+                name,
+                names.join(", ")
+            )
+        }
+        _ => anyhow::bail!(
+            "Instance name {:?} is ambiguous: {} instances share it ({}). Rename one in your config file",
+            name,
+            matches.len(),
+            matches
+                .iter()
+                .map(|i| format!("{:?}", i.path))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
 /// Get the specified instance path, or the default instance path
 pub fn resolve_instance_path(config: &Config, instance_path: Option<&str>) -> std::path::PathBuf {
     match instance_path {
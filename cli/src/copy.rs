@@ -0,0 +1,480 @@
+use crate::filter::StarterFilter;
+use crate::template::TemplateRenderer;
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use log::{debug, info};
+use serde::Serialize;
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// What to do when a starter file already exists in the destination with different contents
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ConflictPolicy {
+    // Don't copy anything and list the conflicting files
+    #[default]
+    Abort,
+    // Keep the existing files
+    Skip,
+    // Replace the existing files
+    Overwrite,
+    // Rename the existing files to <name>.orig, then replace them
+    Backup,
+    // Ask for each conflicting file
+    Prompt,
+}
+
+/// A file a starter writes, relative to the destination directory
+#[derive(Debug)]
+pub struct PlannedFile {
+    /// The file in the starter
+    pub source: PathBuf,
+    /// Where the file goes, relative to the destination. File names are already rendered
+    pub path: PathBuf,
+    /// Rendered contents for templated text files. Other files are copied from `source` as-is
+    pub rendered: Option<String>,
+}
+
+impl PlannedFile {
+    /// `path` with forward slashes, as used in manifests
+    pub fn key(&self) -> String {
+        self.path.to_string_lossy().replace('\\', "/")
+    }
+
+    /// The contents the file will have in the destination
+    pub fn contents(&self) -> Result<Vec<u8>> {
+        match &self.rendered {
+            Some(rendered) => Ok(rendered.clone().into_bytes()),
+            None => fs::read(&self.source).with_context(|| format!("Reading {:?}", self.source)),
+        }
+    }
+
+    /// Size of the file in the destination, in bytes
+    pub fn size(&self) -> Result<u64> {
+        match &self.rendered {
+            Some(rendered) => Ok(rendered.len() as u64),
+            None => Ok(fs::metadata(&self.source)?.len()),
+        }
+    }
+
+    /// Write the file into `dest`, keeping the permissions of the source file
+    fn write_to(&self, dest: &Path) -> Result<()> {
+        let dst_path = dest.join(&self.path);
+        if let Some(parent) = dst_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        match &self.rendered {
+            Some(rendered) => {
+                fs::write(&dst_path, rendered)?;
+                // Generated files, like the manifest, have no source to take permissions from
+                if self.source.is_file() {
+                    fs::set_permissions(&dst_path, fs::metadata(&self.source)?.permissions())?;
+                }
+            }
+            None => {
+                fs::copy(&self.source, &dst_path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// List the files of the starter in `src` that `filter` lets through, sorted by path.
+///
+/// When a `renderer` is given, file names and UTF-8 file contents are rendered through it.
+/// Binary files are always copied as-is.
+pub fn plan_dir(
+    src: &Path,
+    renderer: Option<&TemplateRenderer>,
+    filter: &StarterFilter,
+) -> Result<Vec<PlannedFile>> {
+    let mut files = Vec::new();
+    plan_dir_into(
+        src,
+        Path::new(""),
+        Path::new(""),
+        renderer,
+        filter,
+        &mut files,
+    )?;
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+fn plan_dir_into(
+    src: &Path,
+    src_rel: &Path,
+    rel: &Path,
+    renderer: Option<&TemplateRenderer>,
+    filter: &StarterFilter,
+    files: &mut Vec<PlannedFile>,
+) -> Result<()> {
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let src_path = entry.path();
+        let file_name = entry.file_name();
+
+        // Filters match files as they are named in the starter, before rendering
+        let src_rel_path = src_rel.join(&file_name);
+        if !filter.is_included(&src_rel_path, file_type.is_dir()) {
+            debug!("Leaving out {:?}", src_rel_path);
+            continue;
+        }
+
+        let path = match renderer {
+            Some(renderer) => rel.join(
+                renderer
+                    .render_file_name(&file_name.to_string_lossy())
+                    .with_context(|| format!("Rendering file name {:?}", src_path))?,
+            ),
+            None => rel.join(&file_name),
+        };
+
+        if file_type.is_dir() {
+            plan_dir_into(&src_path, &src_rel_path, &path, renderer, filter, files)?;
+            continue;
+        }
+
+        let rendered = match renderer {
+            Some(renderer) => match String::from_utf8(fs::read(&src_path)?) {
+                Ok(contents) => Some(
+                    renderer
+                        .render(&contents)
+                        .with_context(|| format!("Rendering file {:?}", src_path))?,
+                ),
+                Err(_) => {
+                    debug!("Copying binary file {:?} without rendering", src_path);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        files.push(PlannedFile {
+            source: src_path,
+            path,
+            rendered,
+        });
+    }
+
+    Ok(())
+}
+
+/// What copying a file into the destination does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileAction {
+    /// The file doesn't exist yet
+    Create,
+    /// The file exists with the same contents and is left alone
+    Unchanged,
+    /// The existing file is replaced
+    Overwrite,
+    /// The existing file is renamed to `<name>.orig`, then replaced
+    Backup,
+    /// The existing file is kept
+    Skip,
+    /// The file exists with different contents and the policy doesn't say what to do
+    Conflict,
+}
+
+impl fmt::Display for FileAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FileAction::Create => "create",
+            FileAction::Unchanged => "unchanged",
+            FileAction::Overwrite => "overwrite",
+            FileAction::Backup => "backup",
+            FileAction::Skip => "skip",
+            FileAction::Conflict => "conflict",
+        };
+        f.pad(name)
+    }
+}
+
+/// Work out what copying each of `files` into `dest` does under `policy`, without writing
+/// anything. With `ConflictPolicy::Abort` and `ConflictPolicy::Prompt`, files that exist with
+/// different contents are a `FileAction::Conflict`.
+pub fn plan_actions<'a>(
+    files: &'a [PlannedFile],
+    dest: &Path,
+    policy: ConflictPolicy,
+) -> Result<Vec<(&'a PlannedFile, FileAction)>> {
+    let mut actions = Vec::new();
+    for file in files {
+        let dst_path = dest.join(&file.path);
+        let action = if !dst_path.exists() {
+            FileAction::Create
+        } else if dst_path.is_file() && fs::read(&dst_path)? == file.contents()? {
+            FileAction::Unchanged
+        } else {
+            match policy {
+                ConflictPolicy::Skip => FileAction::Skip,
+                ConflictPolicy::Overwrite => FileAction::Overwrite,
+                ConflictPolicy::Backup => FileAction::Backup,
+                ConflictPolicy::Abort | ConflictPolicy::Prompt => FileAction::Conflict,
+            }
+        };
+        actions.push((file, action));
+    }
+    Ok(actions)
+}
+
+/// Copy `files` into `dest`, resolving files that already exist there according to `policy`.
+///
+/// Conflicts are detected before anything is written, so `ConflictPolicy::Abort` leaves `dest`
+/// untouched. Files that already exist with the same contents are left alone.
+///
+/// Returns the files that now match the starter, i.e. all of `files` except skipped ones.
+pub fn copy_files<'a>(
+    files: &'a [PlannedFile],
+    dest: &Path,
+    policy: ConflictPolicy,
+) -> Result<Vec<&'a PlannedFile>> {
+    let stdin = io::stdin();
+    copy_files_with_input(files, dest, policy, &mut stdin.lock())
+}
+
+fn copy_files_with_input<'a>(
+    files: &'a [PlannedFile],
+    dest: &Path,
+    policy: ConflictPolicy,
+    input: &mut dyn BufRead,
+) -> Result<Vec<&'a PlannedFile>> {
+    let mut actions = plan_actions(files, dest, policy)?;
+
+    let conflicts: Vec<&PlannedFile> = actions
+        .iter()
+        .filter(|(_, action)| *action == FileAction::Conflict)
+        .map(|(file, _)| *file)
+        .collect();
+    if policy == ConflictPolicy::Abort && !conflicts.is_empty() {
+        anyhow::bail!(
+            "{} files already exist in {:?} with different contents:\n  {}\nPass --on-conflict=skip|overwrite|backup|prompt to decide what to do with them",
+            conflicts.len(),
+            dest,
+            conflicts
+                .iter()
+                .map(|f| f.path.display().to_string())
+                .collect::<Vec<_>>()
+                .join("\n  ")
+        );
+    }
+    for (file, action) in actions.iter_mut() {
+        if *action == FileAction::Conflict {
+            *action = prompt_action(&file.path, input)?;
+        }
+    }
+
+    fs::create_dir_all(dest)?;
+    let mut copied = Vec::new();
+    for (file, action) in actions {
+        let dst_path = dest.join(&file.path);
+        match action {
+            FileAction::Unchanged => {
+                debug!("{} is unchanged", file.path.display());
+                copied.push(file);
+                continue;
+            }
+            FileAction::Skip => {
+                info!("Skipped existing {}", file.path.display());
+                continue;
+            }
+            FileAction::Backup => {
+                let backup_path = backup_path_for(&dst_path);
+                fs::rename(&dst_path, &backup_path)?;
+                info!(
+                    "Backed up existing {} to {}",
+                    file.path.display(),
+                    backup_path.display()
+                );
+            }
+            FileAction::Overwrite => {
+                if dst_path.is_dir() {
+                    fs::remove_dir_all(&dst_path)?;
+                }
+                info!("Overwrote {}", file.path.display());
+            }
+            FileAction::Create => {}
+            FileAction::Conflict => unreachable!("Conflicts are resolved above"),
+        }
+        file.write_to(dest)
+            .with_context(|| format!("Writing {:?}", dst_path))?;
+        copied.push(file);
+    }
+
+    Ok(copied)
+}
+
+/// Ask on stderr whether to overwrite, back up or skip the existing file at `path`
+fn prompt_action(path: &Path, input: &mut dyn BufRead) -> Result<FileAction> {
+    loop {
+        eprint!(
+            "{} already exists. [o]verwrite, [b]ackup and overwrite, [s]kip or [a]bort? ",
+            path.display()
+        );
+        io::stderr().flush()?;
+
+        let mut answer = String::new();
+        if input.read_line(&mut answer)? == 0 {
+            anyhow::bail!("Aborted: no answer for conflicting file {:?}", path);
+        }
+        match answer.trim().to_lowercase().as_str() {
+            "o" | "overwrite" => return Ok(FileAction::Overwrite),
+            "b" | "backup" => return Ok(FileAction::Backup),
+            "s" | "skip" => return Ok(FileAction::Skip),
+            "a" | "abort" => anyhow::bail!("Aborted at conflicting file {:?}", path),
+            _ => continue,
+        }
+    }
+}
+
+/// `<path>.orig`, or `<path>.orig.1` etc. if that already exists
+fn backup_path_for(path: &Path) -> PathBuf {
+    let with_suffix = |suffix: &str| {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(suffix);
+        path.with_file_name(name)
+    };
+    let mut backup_path = with_suffix(".orig");
+    let mut n = 1;
+    while backup_path.exists() {
+        backup_path = with_suffix(&format!(".orig.{}", n));
+        n += 1;
+    }
+    backup_path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_copy_files() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let src_dir = temp_dir.path().join("src");
+        let dest_dir = temp_dir.path().join("dest");
+
+        // Create source directory with some files
+        fs::create_dir_all(src_dir.join("nested"))?;
+        fs::write(src_dir.join("file1.txt"), "test content 1")?;
+        fs::write(src_dir.join("nested/file2.txt"), "test content 2")?;
+        // Add configuration files - these should be excluded
+        fs::write(
+            src_dir.join("jump-start.yaml"),
+            "name: test-starter\ndescription: Test",
+        )?;
+        fs::write(src_dir.join("degit.json"), "{\"action\": \"remove\"}")?;
+
+        let files = plan_dir(&src_dir, None, &StarterFilter::default())?;
+        copy_files(&files, &dest_dir, ConflictPolicy::Abort)?;
+
+        // Verify files were copied correctly
+        assert!(dest_dir.join("file1.txt").exists());
+        assert!(dest_dir.join("nested/file2.txt").exists());
+
+        // Verify configuration files were NOT copied
+        assert!(!dest_dir.join("jump-start.yaml").exists());
+        assert!(!dest_dir.join("degit.json").exists());
+
+        // Check file contents
+        let file1_content = fs::read_to_string(dest_dir.join("file1.txt"))?;
+        assert_eq!(file1_content, "test content 1");
+
+        // Copying again is not a conflict, since nothing changed
+        copy_files(&files, &dest_dir, ConflictPolicy::Abort)?;
+        Ok(())
+    }
+
+    /// A starter with file1.txt and nested/file2.txt, and a destination where both were edited
+    fn setup_conflict() -> Result<(tempfile::TempDir, Vec<PlannedFile>, PathBuf)> {
+        let temp_dir = tempdir()?;
+        let src_dir = temp_dir.path().join("src");
+        let dest_dir = temp_dir.path().join("dest");
+        fs::create_dir_all(src_dir.join("nested"))?;
+        fs::write(src_dir.join("file1.txt"), "new 1")?;
+        fs::write(src_dir.join("nested/file2.txt"), "new 2")?;
+        fs::write(src_dir.join("file3.txt"), "new 3")?;
+        fs::create_dir_all(dest_dir.join("nested"))?;
+        fs::write(dest_dir.join("file1.txt"), "mine 1")?;
+        fs::write(dest_dir.join("nested/file2.txt"), "mine 2")?;
+
+        let files = plan_dir(&src_dir, None, &StarterFilter::default())?;
+        Ok((temp_dir, files, dest_dir))
+    }
+
+    #[test]
+    fn test_copy_files_conflict_policies() -> Result<()> {
+        let (_temp_dir, files, dest) = setup_conflict()?;
+        let actions = plan_actions(&files, &dest, ConflictPolicy::Abort)?;
+        assert_eq!(
+            actions
+                .iter()
+                .map(|(f, a)| (f.path.to_string_lossy().to_string(), *a))
+                .collect::<Vec<_>>(),
+            vec![
+                ("file1.txt".to_string(), FileAction::Conflict),
+                ("file3.txt".to_string(), FileAction::Create),
+                ("nested/file2.txt".to_string(), FileAction::Conflict),
+            ]
+        );
+        let actions = plan_actions(&files, &dest, ConflictPolicy::Backup)?;
+        assert_eq!(actions[0].1, FileAction::Backup);
+
+        let err = copy_files(&files, &dest, ConflictPolicy::Abort).unwrap_err();
+        assert!(err.to_string().contains("2 files already exist"), "{}", err);
+        assert!(err.to_string().contains("nested/file2.txt"), "{}", err);
+        assert!(!dest.join("file3.txt").exists());
+
+        let copied = copy_files(&files, &dest, ConflictPolicy::Skip)?;
+        assert_eq!(copied.len(), 1);
+        assert_eq!(fs::read_to_string(dest.join("file1.txt"))?, "mine 1");
+        assert_eq!(fs::read_to_string(dest.join("file3.txt"))?, "new 3");
+
+        copy_files(&files, &dest, ConflictPolicy::Backup)?;
+        assert_eq!(fs::read_to_string(dest.join("file1.txt"))?, "new 1");
+        assert_eq!(fs::read_to_string(dest.join("file1.txt.orig"))?, "mine 1");
+        assert_eq!(
+            fs::read_to_string(dest.join("nested/file2.txt.orig"))?,
+            "mine 2"
+        );
+
+        fs::write(dest.join("file1.txt"), "mine again")?;
+        copy_files(&files, &dest, ConflictPolicy::Backup)?;
+        assert_eq!(fs::read_to_string(dest.join("file1.txt.orig"))?, "mine 1");
+        assert_eq!(
+            fs::read_to_string(dest.join("file1.txt.orig.1"))?,
+            "mine again"
+        );
+
+        fs::write(dest.join("file1.txt"), "mine")?;
+        copy_files(&files, &dest, ConflictPolicy::Overwrite)?;
+        assert_eq!(fs::read_to_string(dest.join("file1.txt"))?, "new 1");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_copy_files_prompt() -> Result<()> {
+        let (_temp_dir, files, dest) = setup_conflict()?;
+
+        // Unknown answers are asked again
+        let mut input = io::Cursor::new("x\ns\nb\n");
+        copy_files_with_input(&files, &dest, ConflictPolicy::Prompt, &mut input)?;
+        assert_eq!(fs::read_to_string(dest.join("file1.txt"))?, "mine 1");
+        assert_eq!(fs::read_to_string(dest.join("nested/file2.txt"))?, "new 2");
+        assert!(dest.join("nested/file2.txt.orig").exists());
+
+        // Running out of input aborts before anything is written
+        fs::write(dest.join("file1.txt"), "mine")?;
+        fs::remove_file(dest.join("file3.txt"))?;
+        let mut input = io::Cursor::new("");
+        assert!(copy_files_with_input(&files, &dest, ConflictPolicy::Prompt, &mut input).is_err());
+        assert!(!dest.join("file3.txt").exists());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+use crate::RemoteStarter;
+use crate::copy::PlannedFile;
+use anyhow::{Context, Result};
+use log::debug;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+/// Name of the degit configuration file starters migrated from degit may carry
+pub const DEGIT_FILE_NAME: &str = "degit.json";
+
+/// An action listed in a starter's `degit.json`, run in order after the starter's files are
+/// gathered
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum DegitAction {
+    /// Add the files of another starter, replacing files at the same paths. `src` is a starter
+    /// identifier as passed to `use`
+    Clone { src: String },
+    /// Leave out files or directories, given relative to the starter
+    Remove { files: Vec<String> },
+}
+
+/// Read the actions in the `degit.json` of `starter_dir`. Starters without one have no actions
+///
+/// ```
+/// use jump_start::degit::{DegitAction, read_actions};
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// assert_eq!(read_actions(dir.path()).unwrap(), vec![]);
+///
+/// std::fs::write(
+///     dir.path().join("degit.json"),
+///     r#"[{"action": "remove", "files": ["LICENSE"]}]"#,
+/// )
+/// .unwrap();
+/// assert_eq!(
+///     read_actions(dir.path()).unwrap(),
+///     vec![DegitAction::Remove { files: vec!["LICENSE".to_string()] }]
+/// );
+/// ```
+pub fn read_actions(starter_dir: &Path) -> Result<Vec<DegitAction>> {
+    let path = starter_dir.join(DEGIT_FILE_NAME);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = fs::read_to_string(&path)?;
+    let actions: Vec<DegitAction> = serde_json::from_str(&contents).with_context(|| {
+        format!(
+            "Could not parse {:?}. Supported actions are \"clone\" and \"remove\"",
+            path
+        )
+    })?;
+    // Refs of remote sources are passed to git, so reject any it would read as an option
+    for action in &actions {
+        if let DegitAction::Clone { src } = action
+            && RemoteStarter::is_remote_identifier(src)
+            && RemoteStarter::from_path(src).is_none()
+        {
+            anyhow::bail!("Invalid clone src {:?} in {:?}", src, path);
+        }
+    }
+    Ok(actions)
+}
+
+/// Drop the planned files at `paths`, or under them for directories
+pub fn remove_files(files: &mut Vec<PlannedFile>, paths: &[String]) {
+    for path in paths {
+        let path = path.trim_start_matches("./").trim_end_matches('/');
+        let dir_prefix = format!("{}/", path);
+        let before = files.len();
+        files.retain(|f| {
+            let key = f.key();
+            key != path && !key.starts_with(&dir_prefix)
+        });
+        if files.len() == before {
+            debug!("Nothing to remove at {:?}", path);
+        }
+    }
+}
+
+/// Add `cloned` files to `files`, replacing any at the same paths, keeping them sorted by path
+pub fn merge_files(files: &mut Vec<PlannedFile>, cloned: Vec<PlannedFile>) {
+    files.retain(|f| !cloned.iter().any(|c| c.path == f.path));
+    files.extend(cloned);
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    fn planned(path: &str) -> PlannedFile {
+        PlannedFile {
+            source: PathBuf::from("/starter").join(path),
+            path: PathBuf::from(path),
+            rendered: None,
+        }
+    }
+
+    fn keys(files: &[PlannedFile]) -> Vec<String> {
+        files.iter().map(PlannedFile::key).collect()
+    }
+
+    #[test]
+    fn test_read_actions() -> Result<()> {
+        let temp_dir = tempdir()?;
+        fs::write(
+            temp_dir.path().join("degit.json"),
+            r#"[
+                {"action": "clone", "src": "@user/repo/group/base", "cache": false},
+                {"action": "remove", "files": ["LICENSE", "docs/"]}
+            ]"#,
+        )?;
+        assert_eq!(
+            read_actions(temp_dir.path())?,
+            vec![
+                DegitAction::Clone {
+                    src: "@user/repo/group/base".to_string()
+                },
+                DegitAction::Remove {
+                    files: vec!["LICENSE".to_string(), "docs/".to_string()]
+                },
+            ]
+        );
+
+        fs::write(
+            temp_dir.path().join("degit.json"),
+            r#"[{"action": "rename", "files": ["a"]}]"#,
+        )?;
+        let err = read_actions(temp_dir.path()).unwrap_err();
+        assert!(
+            format!("{:#}", err).contains("unknown variant `rename`"),
+            "{:#}",
+            err
+        );
+
+        fs::write(
+            temp_dir.path().join("degit.json"),
+            r#"[{"action": "clone", "src": "@user/repo/group/base@--upload-pack=touch pwned"}]"#,
+        )?;
+        let err = read_actions(temp_dir.path()).unwrap_err();
+        assert!(
+            format!("{:#}", err).contains("Invalid clone src"),
+            "{:#}",
+            err
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_remove_and_merge_files() {
+        let mut files = vec![
+            planned("LICENSE"),
+            planned("README.md"),
+            planned("docs/index.md"),
+            planned("docs-site/index.md"),
+        ];
+        remove_files(
+            &mut files,
+            &[
+                "./LICENSE".to_string(),
+                "docs/".to_string(),
+                "missing".to_string(),
+            ],
+        );
+        assert_eq!(keys(&files), vec!["README.md", "docs-site/index.md"]);
+
+        merge_files(&mut files, vec![planned("README.md"), planned("Makefile")]);
+        assert_eq!(
+            keys(&files),
+            vec!["Makefile", "README.md", "docs-site/index.md"]
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use crate::degit::DEGIT_FILE_NAME;
+use crate::starter::StarterConfig;
+use anyhow::{Context, Result};
+use ignore::WalkBuilder;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::path::Path;
+
+/// Name of the file in a starter listing files to leave out, in .gitignore syntax
+pub const IGNORE_FILE_NAME: &str = ".jumpstartignore";
+
+/// Configuration files, which are never copied or previewed
+const CONFIG_FILE_NAMES: [&str; 3] = ["jump-start.yaml", DEGIT_FILE_NAME, IGNORE_FILE_NAME];
+
+/// Directories never searched for starters
+const PRUNED_DIR_NAMES: [&str; 2] = [".git", "node_modules"];
+
+/// Walk the instance in `instance_dir`, in file name order, looking for starters.
+///
+/// Directories ignored by the instance's .gitignore files or by a .jumpstartignore at its root
+/// are not walked into, nor are .git and node_modules directories.
+pub fn walk_instance(instance_dir: &Path) -> ignore::Walk {
+    let mut builder = WalkBuilder::new(instance_dir);
+    builder
+        .standard_filters(false)
+        .git_ignore(true)
+        .require_git(false)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .filter_entry(|entry| {
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            !(is_dir && PRUNED_DIR_NAMES.iter().any(|n| entry.file_name() == *n))
+        });
+    let ignore_file = instance_dir.join(IGNORE_FILE_NAME);
+    if ignore_file.is_file() {
+        builder.add_ignore(ignore_file);
+    }
+    builder.build()
+}
+
+/// Decides which files of a starter are copied by `use` and shown in previews.
+///
+/// Files are left out if they match an `exclude` pattern in the starter's jump-start.yaml or a
+/// line of its .jumpstartignore. If the starter lists `include` patterns, only files matching
+/// one of them are kept. Patterns use .gitignore syntax, relative to the starter.
+#[derive(Debug)]
+pub struct StarterFilter {
+    include: Option<Gitignore>,
+    exclude: Gitignore,
+}
+
+impl Default for StarterFilter {
+    /// A filter leaving out configuration files only
+    fn default() -> Self {
+        Self {
+            include: None,
+            exclude: Gitignore::empty(),
+        }
+    }
+}
+
+impl StarterFilter {
+    /// Build the filter for the starter in `starter_dir` with configuration `config`
+    pub fn new(starter_dir: &Path, config: Option<&StarterConfig>) -> Result<Self> {
+        let patterns = |list: Option<&Vec<String>>| list.cloned().unwrap_or_default();
+        let include = patterns(config.and_then(|c| c.include.as_ref()));
+        let exclude = patterns(config.and_then(|c| c.exclude.as_ref()));
+
+        let mut builder = GitignoreBuilder::new(starter_dir);
+        for pattern in &exclude {
+            builder
+                .add_line(None, pattern)
+                .with_context(|| format!("Invalid exclude pattern {:?}", pattern))?;
+        }
+        let ignore_file = starter_dir.join(IGNORE_FILE_NAME);
+        if ignore_file.is_file()
+            && let Some(e) = builder.add(&ignore_file)
+        {
+            return Err(e).with_context(|| format!("Could not read {:?}", ignore_file));
+        }
+        let exclude = builder.build()?;
+
+        let include = if include.is_empty() {
+            None
+        } else {
+            let mut builder = GitignoreBuilder::new(starter_dir);
+            for pattern in &include {
+                builder
+                    .add_line(None, pattern)
+                    .with_context(|| format!("Invalid include pattern {:?}", pattern))?;
+            }
+            Some(builder.build()?)
+        };
+
+        Ok(Self { include, exclude })
+    }
+
+    /// Whether to copy the file (or walk into the directory) at `path`, relative to the starter
+    pub fn is_included(&self, path: &Path, is_dir: bool) -> bool {
+        if !is_dir
+            && let Some(name) = path.file_name()
+            && CONFIG_FILE_NAMES.iter().any(|n| name == *n)
+        {
+            return false;
+        }
+        if self.exclude.matched(path, is_dir).is_ignore() {
+            return false;
+        }
+        // Directories are walked into, since files in them may be included
+        match &self.include {
+            Some(include) if !is_dir => {
+                include.matched_path_or_any_parents(path, false).is_ignore()
+            }
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_starter_filter() -> Result<()> {
+        let temp_dir = tempdir()?;
+        fs::write(
+            temp_dir.path().join(IGNORE_FILE_NAME),
+            "# Build output\ndist/\n*.pyc\n!keep.pyc\n",
+        )?;
+        let config: StarterConfig = "
+include: [src/, README.md, '*.pyc']
+exclude: [.DS_Store, node_modules]
+"
+        .parse()?;
+        let filter = StarterFilter::new(temp_dir.path(), Some(&config))?;
+        let included = |path: &str, is_dir: bool| filter.is_included(Path::new(path), is_dir);
+
+        assert!(included("src/main.py", false));
+        assert!(included("src/nested/util.py", false));
+        assert!(included("README.md", false));
+        assert!(included("tests", true));
+        assert!(!included("tests/test_main.py", false));
+        assert!(!included("src/.DS_Store", false));
+        assert!(!included("src/node_modules", true));
+        assert!(!included("dist", true));
+        assert!(!included("src/cache.pyc", false));
+        assert!(included("keep.pyc", false));
+        assert!(!included("jump-start.yaml", false));
+        assert!(!included(IGNORE_FILE_NAME, false));
+
+        let filter = StarterFilter::default();
+        assert!(filter.is_included(Path::new(".DS_Store"), false));
+        assert!(!filter.is_included(Path::new("degit.json"), false));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+use anyhow::{Context, Result};
+use log::{info, warn};
+use std::path::Path;
+use std::process::Command;
+
+/// What hooks are told about the starter being used, passed as environment variables
+#[derive(Debug)]
+pub struct HookEnv<'a> {
+    /// The starter identifier passed to `use`, as JUMP_START_STARTER
+    pub starter: &'a str,
+    /// The starter's name, without its group or instance, as JUMP_START_STARTER_NAME
+    pub starter_name: &'a str,
+    /// Directory holding the starter's files, as JUMP_START_STARTER_DIR
+    pub starter_dir: &'a Path,
+    /// The destination directory, which hooks run in, as JUMP_START_DEST
+    pub dest: &'a Path,
+}
+
+impl HookEnv<'_> {
+    fn vars(&self) -> [(&'static str, &std::ffi::OsStr); 4] {
+        [
+            ("JUMP_START_STARTER", self.starter.as_ref()),
+            ("JUMP_START_STARTER_NAME", self.starter_name.as_ref()),
+            ("JUMP_START_STARTER_DIR", self.starter_dir.as_os_str()),
+            ("JUMP_START_DEST", self.dest.as_os_str()),
+        ]
+    }
+}
+
+/// Run the shell `commands` of the hook `stage` (e.g. "post_use") in order, in `env.dest`.
+///
+/// Stops at the first command that fails, naming its step and the steps that were skipped.
+pub fn run_hooks(stage: &str, commands: &[String], env: &HookEnv) -> Result<()> {
+    for (i, command) in commands.iter().enumerate() {
+        let step = format!("{} hook {}/{}", stage, i + 1, commands.len());
+        info!("Running {}: {}", step, command);
+
+        let status = shell(command)
+            .current_dir(env.dest)
+            .envs(env.vars())
+            .status()
+            .with_context(|| format!("Could not run {} `{}`", step, command))?;
+        if !status.success() {
+            let skipped = &commands[i + 1..];
+            if !skipped.is_empty() {
+                warn!(
+                    "Skipped the remaining {} hooks: {}",
+                    stage,
+                    skipped.join("; ")
+                );
+            }
+            anyhow::bail!("{} `{}` failed ({})", step, command, status);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.args(["/C", command]);
+    shell
+}
+
+#[cfg(not(windows))]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.args(["-c", command]);
+    shell
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_run_hooks() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let env = HookEnv {
+            starter: "group/starter",
+            starter_name: "starter",
+            starter_dir: Path::new("/starters/group/starter"),
+            dest: temp_dir.path(),
+        };
+
+        let commands = vec![
+            "echo \"$JUMP_START_STARTER $JUMP_START_STARTER_NAME $JUMP_START_STARTER_DIR\" > env.txt"
+                .to_string(),
+            "pwd > pwd.txt".to_string(),
+        ];
+        run_hooks("post_use", &commands, &env)?;
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("env.txt"))?,
+            "group/starter starter /starters/group/starter\n"
+        );
+        assert_eq!(
+            fs::canonicalize(fs::read_to_string(temp_dir.path().join("pwd.txt"))?.trim())?,
+            fs::canonicalize(temp_dir.path())?
+        );
+
+        let commands = vec![
+            "true".to_string(),
+            "exit 3".to_string(),
+            "touch skipped.txt".to_string(),
+        ];
+        let err = run_hooks("post_use", &commands, &env).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("post_use hook 2/3 `exit 3` failed (exit status: 3)"),
+            "{}",
+            err
+        );
+        assert!(!temp_dir.path().join("skipped.txt").exists());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,393 @@
+use crate::Config;
+use anyhow::Result;
+use reqwest::header::{AUTHORIZATION, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The kind of git forge a host runs, which determines its archive URLs
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HostKind {
+    Github,
+    Gitlab,
+    /// Gitea and Forgejo, e.g. Codeberg
+    Gitea,
+    Bitbucket,
+}
+
+impl HostKind {
+    /// Environment variable a token for this kind of host is read from by default
+    pub fn token_env_var(&self) -> &'static str {
+        match self {
+            HostKind::Github => "GITHUB_TOKEN",
+            HostKind::Gitlab => "GITLAB_TOKEN",
+            HostKind::Gitea => "GITEA_TOKEN",
+            HostKind::Bitbucket => "BITBUCKET_TOKEN",
+        }
+    }
+}
+
+/// An access token. Its value is never included in `Debug` output, so it can't end up in logs.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Token(String);
+
+impl Token {
+    pub fn new(value: &str) -> Self {
+        Self(value.to_string())
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Token(<redacted>)")
+    }
+}
+
+/// A host that remote starters can be downloaded from.
+///
+/// Remote starter identifiers choose a host by name with a prefix, e.g.
+/// "gitlab:@user/repo/group/name". Hosts without a prefix use the default host, which is GitHub
+/// unless a configured host is marked as the default.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Host {
+    /// Name used as the identifier prefix and as the cache directory
+    pub name: String,
+    pub kind: HostKind,
+    /// Base URL of the host, e.g. "https://gitlab.example.com"
+    pub url: String,
+    /// Base URL used to clone in git mode, e.g. "git@gitlab.example.com:". Defaults to `url`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_url: Option<String>,
+    /// Use this host for identifiers without a prefix
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<bool>,
+    /// Access token for private repositories. Prefer `token_env` to keep it out of the config
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<Token>,
+    /// Environment variable to read the token from. Defaults to e.g. GITHUB_TOKEN or
+    /// GITLAB_TOKEN depending on `kind`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_env: Option<String>,
+}
+
+/// The host used for identifiers without a prefix, unless configured otherwise
+pub const DEFAULT_HOST: &str = "github";
+
+impl Host {
+    fn new(name: &str, kind: HostKind, url: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            url: url.to_string(),
+            git_url: None,
+            default: None,
+            token: None,
+            token_env: None,
+        }
+    }
+
+    /// Name of the environment variable this host's token is read from
+    pub fn token_env_var(&self) -> &str {
+        self.token_env
+            .as_deref()
+            .unwrap_or_else(|| self.kind.token_env_var())
+    }
+
+    /// The header that authenticates requests to this host, if it has a token
+    pub fn auth_header(&self) -> Result<Option<(HeaderName, HeaderValue)>> {
+        let Some(token) = &self.token else {
+            return Ok(None);
+        };
+        let (name, value) = match self.kind {
+            HostKind::Github | HostKind::Bitbucket => {
+                (AUTHORIZATION, format!("Bearer {}", token.expose()))
+            }
+            HostKind::Gitlab => (
+                HeaderName::from_static("private-token"),
+                token.expose().to_string(),
+            ),
+            HostKind::Gitea => (AUTHORIZATION, format!("token {}", token.expose())),
+        };
+        let mut value = HeaderValue::from_str(&value)
+            .map_err(|_| anyhow::anyhow!("Token for host {:?} is not a valid header", self.name))?;
+        // Keeps the value out of Debug output
+        value.set_sensitive(true);
+        Ok(Some((name, value)))
+    }
+
+    /// Hosts that are available without any configuration
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "github" => Some(Self::new(name, HostKind::Github, "https://www.github.com")),
+            "gitlab" => Some(Self::new(name, HostKind::Gitlab, "https://gitlab.com")),
+            "codeberg" => Some(Self::new(name, HostKind::Gitea, "https://codeberg.org")),
+            "bitbucket" => Some(Self::new(
+                name,
+                HostKind::Bitbucket,
+                "https://bitbucket.org",
+            )),
+            _ => None,
+        }
+    }
+
+    /// URL of the tar.gz archive of `owner/repo` at `git_ref`, which may be "HEAD".
+    ///
+    /// GitHub only accepts tokens through its API, so authenticated GitHub hosts use the API's
+    /// tarball endpoint.
+    ///
+    /// ```
+    /// use jump_start::host::Host;
+    /// let github = Host::builtin("github").unwrap();
+    /// assert_eq!(
+    ///     github.archive_url("kevinschaul", "jump-start", "HEAD"),
+    ///     "https://www.github.com/kevinschaul/jump-start/archive/HEAD.tar.gz"
+    /// );
+    ///
+    /// let gitlab = Host::builtin("gitlab").unwrap();
+    /// assert_eq!(
+    ///     gitlab.archive_url("kevinschaul", "jump-start", "v1.2"),
+    ///     "https://gitlab.com/api/v4/projects/kevinschaul%2Fjump-start/repository/archive.tar.gz?sha=v1.2"
+    /// );
+    /// ```
+    pub fn archive_url(&self, owner: &str, repo: &str, git_ref: &str) -> String {
+        let base = self.url.trim_end_matches('/');
+        match self.kind {
+            HostKind::Github if self.token.is_some() => {
+                let api = if base.ends_with("://github.com") || base.ends_with("://www.github.com")
+                {
+                    "https://api.github.com".to_string()
+                } else {
+                    // GitHub Enterprise
+                    format!("{}/api/v3", base)
+                };
+                format!("{}/repos/{}/{}/tarball/{}", api, owner, repo, git_ref)
+            }
+            HostKind::Github => format!("{}/{}/{}/archive/{}.tar.gz", base, owner, repo, git_ref),
+            HostKind::Gitlab => {
+                let url = format!(
+                    "{}/api/v4/projects/{}%2F{}/repository/archive.tar.gz",
+                    base, owner, repo
+                );
+                // Without a sha, GitLab archives the default branch
+                if git_ref == "HEAD" {
+                    url
+                } else {
+                    format!("{}?sha={}", url, git_ref.replace('/', "%2F"))
+                }
+            }
+            HostKind::Gitea => format!(
+                "{}/api/v1/repos/{}/{}/archive/{}.tar.gz",
+                base, owner, repo, git_ref
+            ),
+            HostKind::Bitbucket => format!("{}/{}/{}/get/{}.tar.gz", base, owner, repo, git_ref),
+        }
+    }
+
+    /// URL to clone `owner/repo` with git
+    ///
+    /// ```
+    /// use jump_start::host::Host;
+    /// let mut gitlab = Host::builtin("gitlab").unwrap();
+    /// assert_eq!(gitlab.clone_url("user", "repo"), "https://gitlab.com/user/repo.git");
+    ///
+    /// gitlab.git_url = Some("git@gitlab.com:".to_string());
+    /// assert_eq!(gitlab.clone_url("user", "repo"), "git@gitlab.com:user/repo.git");
+    /// ```
+    pub fn clone_url(&self, owner: &str, repo: &str) -> String {
+        let base = match &self.git_url {
+            Some(git_url) => git_url.clone(),
+            // github.com redirects www, but git is happier without the redirect
+            None => format!(
+                "{}/",
+                self.url.trim_end_matches('/').replace("://www.", "://")
+            ),
+        };
+        format!("{}{}/{}.git", base, owner, repo)
+    }
+}
+
+/// Find the host named `name`, or the default host if `name` is `None`.
+///
+/// Configured hosts take precedence over the built-in ones, so e.g. GitHub Enterprise can be
+/// used by configuring a host named "github" with its URL.
+pub fn resolve_host(config: &Config, name: Option<&str>) -> Result<Host> {
+    resolve_host_with(config, name, |key| std::env::var(key).ok())
+}
+
+/// [`resolve_host`], reading environment variables with `env` so tests don't have to change
+/// the process environment
+pub fn resolve_host_with(
+    config: &Config,
+    name: Option<&str>,
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<Host> {
+    let mut host = match name {
+        Some(name) => match config.hosts.iter().find(|h| h.name == name) {
+            Some(host) => host.clone(),
+            None => Host::builtin(name).ok_or_else(|| {
+                let configured: Vec<&str> = config.hosts.iter().map(|h| h.name.as_str()).collect();
+                anyhow::anyhow!(
+                    "Unknown host {:?}. Use one of github, gitlab, codeberg, bitbucket{}{}, or add it to \"hosts\" in your config file",
+                    name,
+                    if configured.is_empty() { "" } else { ", " },
+                    configured.join(", ")
+                )
+            })?,
+        },
+        None => match config.hosts.iter().find(|h| h.default.unwrap_or(false)) {
+            Some(host) => host.clone(),
+            None => config
+                .hosts
+                .iter()
+                .find(|h| h.name == DEFAULT_HOST)
+                .cloned()
+                .unwrap_or_else(|| Host::builtin(DEFAULT_HOST).unwrap()),
+        },
+    };
+
+    if host.token.is_none()
+        && let Some(token) = env(host.token_env_var())
+        && !token.is_empty()
+    {
+        host.token = Some(Token::new(&token));
+    }
+
+    // `git_base_url` predates per-host configuration and applies to GitHub
+    if host.kind == HostKind::Github
+        && host.git_url.is_none()
+        && let Some(git_base_url) = &config.git_base_url
+    {
+        host.git_url = Some(git_base_url.clone());
+    }
+
+    Ok(host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_urls() {
+        let host = |kind| Host::new("test", kind, "https://example.com/");
+
+        assert_eq!(
+            host(HostKind::Github).archive_url("user", "repo", "v1"),
+            "https://example.com/user/repo/archive/v1.tar.gz"
+        );
+        assert_eq!(
+            host(HostKind::Gitlab).archive_url("user", "repo", "HEAD"),
+            "https://example.com/api/v4/projects/user%2Frepo/repository/archive.tar.gz"
+        );
+        assert_eq!(
+            host(HostKind::Gitlab).archive_url("user", "repo", "feature/x"),
+            "https://example.com/api/v4/projects/user%2Frepo/repository/archive.tar.gz?sha=feature%2Fx"
+        );
+        assert_eq!(
+            host(HostKind::Gitea).archive_url("user", "repo", "HEAD"),
+            "https://example.com/api/v1/repos/user/repo/archive/HEAD.tar.gz"
+        );
+        assert_eq!(
+            host(HostKind::Bitbucket).archive_url("user", "repo", "abc1234"),
+            "https://example.com/user/repo/get/abc1234.tar.gz"
+        );
+    }
+
+    #[test]
+    fn test_token_is_redacted() -> Result<()> {
+        let mut host = Host::new("work", HostKind::Gitlab, "https://gitlab.example.com");
+        host.token = Some(Token::new("glpat-secret"));
+
+        assert!(!format!("{:?}", host).contains("glpat-secret"));
+        let (name, value) = host.auth_header()?.unwrap();
+        assert_eq!(name.as_str(), "private-token");
+        assert_eq!(value.to_str()?, "glpat-secret");
+        assert!(!format!("{:?}", value).contains("glpat-secret"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_host_token_from_env() -> Result<()> {
+        let mut config = Config::default();
+        let mut host = Host::new("work", HostKind::Gitea, "https://git.example.com");
+        host.token_env = Some("WORK_TOKEN".to_string());
+        config.hosts = vec![host];
+        let env = |key: &str| (key == "WORK_TOKEN").then(|| "secret".to_string());
+
+        assert_eq!(
+            resolve_host_with(&config, Some("work"), |_| None)?.token,
+            None
+        );
+        assert_eq!(
+            resolve_host_with(&config, Some("work"), |_| Some(String::new()))?.token,
+            None
+        );
+
+        let host = resolve_host_with(&config, Some("work"), env)?;
+        assert_eq!(host.token, Some(Token::new("secret")));
+        assert_eq!(host.auth_header()?.unwrap().1.to_str()?, "token secret");
+
+        // Tokens in the config file win over the environment
+        config.hosts[0].token = Some(Token::new("from-config"));
+        assert_eq!(
+            resolve_host_with(&config, Some("work"), env)?.token,
+            Some(Token::new("from-config"))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_github_archive_url_with_token() {
+        let mut github = Host::builtin("github").unwrap();
+        github.token = Some(Token::new("secret"));
+        assert_eq!(
+            github.archive_url("user", "repo", "HEAD"),
+            "https://api.github.com/repos/user/repo/tarball/HEAD"
+        );
+
+        github.url = "https://ghe.example.com".to_string();
+        assert_eq!(
+            github.archive_url("user", "repo", "v1"),
+            "https://ghe.example.com/api/v3/repos/user/repo/tarball/v1"
+        );
+    }
+
+    #[test]
+    fn test_resolve_host() -> Result<()> {
+        let mut config = Config::default();
+
+        assert_eq!(resolve_host(&config, None)?.kind, HostKind::Github);
+        assert_eq!(
+            resolve_host(&config, Some("gitlab"))?.kind,
+            HostKind::Gitlab
+        );
+        assert!(resolve_host(&config, Some("work")).is_err());
+
+        // GitHub Enterprise replaces the built-in GitHub host
+        config.hosts = vec![
+            Host::new("github", HostKind::Github, "https://ghe.example.com"),
+            Host::new("work", HostKind::Gitea, "https://git.example.com"),
+        ];
+        config.git_base_url = Some("git@ghe.example.com:".to_string());
+        let github = resolve_host(&config, None)?;
+        assert_eq!(github.url, "https://ghe.example.com");
+        assert_eq!(
+            github.clone_url("user", "repo"),
+            "git@ghe.example.com:user/repo.git"
+        );
+        assert_eq!(resolve_host(&config, Some("work"))?.kind, HostKind::Gitea);
+
+        // A configured default host is used for identifiers without a prefix
+        config.hosts[1].default = Some(true);
+        assert_eq!(resolve_host(&config, None)?.name, "work");
+
+        Ok(())
+    }
+}
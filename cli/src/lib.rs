@@ -3,9 +3,19 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 
 // Export modules
+pub mod cache;
 pub mod commands;
 pub mod config;
+pub mod copy;
+pub mod degit;
+pub mod filter;
+pub mod git;
+pub mod hooks;
+pub mod host;
+pub mod manifest;
+pub mod schema;
 pub mod starter;
+pub mod template;
 
 // Re-export types for convenience
 pub use config::Config;
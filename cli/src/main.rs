@@ -1,10 +1,16 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use jump_start::{
+    cache::parse_duration,
     commands,
+    commands::list::ListFormat,
+    commands::r#use::{CloneMode, UseOptions},
     config::{get_config_path, load_config},
+    copy::ConflictPolicy,
+    template::parse_key_value,
 };
 use log::{LevelFilter, Log, Metadata, Record, debug, error, set_logger, set_max_level};
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
@@ -27,14 +33,107 @@ enum Commands {
     #[command(arg_required_else_help = true)]
     Use {
         /// The starter to use.
-        /// For local starters: group/starter-name
+        /// For local starters: group/starter-name or instance/group/starter-name
         ///     e.g. react-d3/LineChart
         /// For remote starters: @username/group/starter-name or @username/repo/group/starter-path
         ///     e.g. @kevinschaul/react-d3/LineChart
+        /// Groups can be nested, e.g. frontend/react/dashboard. Remote starters in nested groups
+        /// need the repo, e.g. @kevinschaul/jump-start/frontend/react/dashboard
+        /// Download from another host with a prefix, e.g. gitlab:@username/repo/group/starter-name
+        /// Pin remote starters to a branch or tag with @REF, or to a commit with #SHA
+        ///     e.g. @kevinschaul/react-d3/LineChart@v1.2
         #[clap(verbatim_doc_comment)]
         starter_identifier: String,
         /// Optional destination directory
         dest: Option<String>,
+        /// Set a template variable declared by the starter. May be repeated
+        #[arg(long = "var", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+        vars: Vec<(String, String)>,
+        /// How to fetch remote starters
+        #[arg(long, value_enum, default_value_t = CloneMode::Tar)]
+        mode: CloneMode,
+        /// Check remote starters for changes even if the cached copy is recent
+        #[arg(long)]
+        refresh: bool,
+        /// Instance to take local starters from, instead of the default instance
+        #[arg(long)]
+        instance: Option<String>,
+        /// What to do with files that already exist in the destination
+        #[arg(long, value_enum, default_value_t = ConflictPolicy::Abort)]
+        on_conflict: ConflictPolicy,
+        /// Print the files that would be created, overwritten or skipped without writing them
+        #[arg(long)]
+        dry_run: bool,
+        /// Output the dry-run plan as JSON
+        #[arg(long, requires = "dry_run")]
+        json: bool,
+        /// Don't write the .jump-start.json manifest recording the starter, commit and file
+        /// hashes, which `diff` and `upgrade` compare against
+        #[arg(long)]
+        no_manifest: bool,
+        /// Don't run the commands the starter lists under hooks.post_use
+        #[arg(long)]
+        no_hooks: bool,
+        /// Use remote starters from sources not in trusted_sources without asking
+        #[arg(long)]
+        trust: bool,
+        /// Include an optional feature declared by the starter, e.g. --with docker. May be
+        /// repeated or comma-separated
+        #[arg(long = "with", value_name = "FEATURE", value_delimiter = ',')]
+        with: Vec<String>,
+        /// Open the starter's mainFile in $VISUAL or $EDITOR after copying
+        #[arg(long)]
+        open: bool,
+    },
+
+    /// Merge a starter's changes into a project created from it with `use`
+    #[command(arg_required_else_help = true)]
+    Upgrade {
+        /// The starter to upgrade to, as passed to `use`. Pin a new version with @REF or #SHA
+        starter_identifier: String,
+        /// Project directory. Defaults to the current directory
+        dir: Option<String>,
+        /// Set or change a template variable. Variables passed to `use` are remembered
+        #[arg(long = "var", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+        vars: Vec<(String, String)>,
+        /// How to fetch remote starters
+        #[arg(long, value_enum, default_value_t = CloneMode::Tar)]
+        mode: CloneMode,
+        /// Check remote starters for changes even if the cached copy is recent
+        #[arg(long)]
+        refresh: bool,
+        /// Instance to take local starters from, instead of the default instance
+        #[arg(long)]
+        instance: Option<String>,
+        /// Use remote starters from sources not in trusted_sources without asking
+        #[arg(long)]
+        trust: bool,
+    },
+
+    /// Show how a project differs from its starter
+    Diff {
+        /// The starter to compare with. Defaults to the one recorded by `use`
+        starter_identifier: Option<String>,
+        /// Project directory. Defaults to the current directory
+        dir: Option<String>,
+        /// Summarise changed lines per file instead of printing the diff
+        #[arg(long)]
+        stat: bool,
+        /// Set a template variable. Variables recorded by `use` are remembered
+        #[arg(long = "var", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+        vars: Vec<(String, String)>,
+        /// How to fetch remote starters
+        #[arg(long, value_enum, default_value_t = CloneMode::Tar)]
+        mode: CloneMode,
+        /// Check remote starters for changes even if the cached copy is recent
+        #[arg(long)]
+        refresh: bool,
+        /// Instance to take local starters from, instead of the default instance
+        #[arg(long)]
+        instance: Option<String>,
+        /// Use remote starters from sources not in trusted_sources without asking
+        #[arg(long)]
+        trust: bool,
     },
 
     /// Find a starter
@@ -45,18 +144,62 @@ enum Commands {
         /// Output results as JSON
         #[arg(long)]
         json: bool,
+        /// Treat problems found in instances, like colliding starters, as errors
+        #[arg(long)]
+        strict: bool,
+    },
+
+    /// List the starters in the configured instances
+    List {
+        /// Only list starters in this instance
+        #[arg(long)]
+        instance: Option<String>,
+        /// Only list starters in this group, including groups nested in it
+        #[arg(long)]
+        group: Option<String>,
+        /// How to print the starters
+        #[arg(long, value_enum, default_value_t)]
+        format: ListFormat,
+        /// Treat problems found in instances, like colliding starters, as errors
+        #[arg(long)]
+        strict: bool,
     },
 
     /// Storybook commands
     #[command(subcommand)]
     Storybook(StorybookCommands),
 
+    /// Inspect and prune the cache of remote starters
+    #[command(subcommand)]
+    Cache(CacheCommands),
+
     /// Update readme
     #[command()]
     UpdateReadme {
         /// Path to the instance to operate on
         #[arg(long)]
         instance_path: Option<String>,
+        /// Treat problems found in instances, like colliding starters, as errors
+        #[arg(long)]
+        strict: bool,
+    },
+
+    /// Print the JSON Schema of jump-start.yaml files
+    #[command()]
+    Schema {},
+
+    /// Check the starters in an instance for problems, failing if there are errors
+    Validate {
+        /// Path to the instance to operate on
+        #[arg(long)]
+        instance_path: Option<String>,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+        /// Treat problems found in instances, like colliding starters, as errors
+        #[arg(long)]
+        strict: bool,
     },
 }
 
@@ -70,6 +213,9 @@ enum StorybookCommands {
         /// Port to run Storybook on
         #[arg(short, long, default_value = "6006")]
         port: u16,
+        /// Treat problems found in instances, like colliding starters, as errors
+        #[arg(long)]
+        strict: bool,
     },
 
     /// Build Storybook for production
@@ -80,6 +226,36 @@ enum StorybookCommands {
         /// Output directory
         #[arg(short, long, default_value = "storybook-static")]
         output: String,
+        /// Treat problems found in instances, like colliding starters, as errors
+        #[arg(long)]
+        strict: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheCommands {
+    /// List cached repositories with their size, age and source
+    List {
+        /// Output results as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Print path to the cache directory
+    Path {},
+
+    /// Remove cached repositories
+    Clear {
+        /// Repositories to remove, e.g. github/kevinschaul/jump-start or @kevinschaul/jump-start,
+        /// including their copies pinned to a ref. Removes everything if omitted
+        sources: Vec<String>,
+    },
+
+    /// Remove cached repositories that haven't been updated recently
+    Prune {
+        /// Remove repositories older than this, e.g. 30d, 12h or 45m
+        #[arg(long, value_parser = parse_duration)]
+        older_than: Duration,
     },
 }
 
@@ -92,10 +268,11 @@ fn setup_logger(verbose: bool) {
         }
 
         fn log(&self, record: &Record) {
+            // Log to stderr so stdout only carries output meant for scripts, like JSON reports.
             // Only prefix debug logs, keep everything else clean
             match record.level() {
-                log::Level::Debug | log::Level::Trace => println!("[DEBUG] {}", record.args()),
-                _ => println!("{}", record.args()),
+                log::Level::Debug | log::Level::Trace => eprintln!("[DEBUG] {}", record.args()),
+                _ => eprintln!("{}", record.args()),
             }
         }
 
@@ -114,11 +291,29 @@ fn setup_logger(verbose: bool) {
     set_max_level(max_level);
 }
 
+impl Commands {
+    /// Whether --strict was passed, for the commands that read starters from instances
+    fn strict(&self) -> bool {
+        match self {
+            Commands::Find { strict, .. }
+            | Commands::List { strict, .. }
+            | Commands::UpdateReadme { strict, .. }
+            | Commands::Validate { strict, .. }
+            | Commands::Storybook(
+                StorybookCommands::Dev { strict, .. } | StorybookCommands::Prod { strict, .. },
+            ) => *strict,
+            _ => false,
+        }
+    }
+}
+
 fn handle_command(command: Commands) -> Result<()> {
     match command {
         Commands::Config {} => commands::config::config(),
+        Commands::Schema {} => commands::schema::schema(),
         _ => {
-            let config = load_and_validate_config()?;
+            let mut config = load_and_validate_config()?;
+            config.strict |= command.strict();
             execute_config_dependent_command(command, config)
         }
     }
@@ -154,23 +349,117 @@ fn execute_config_dependent_command(
         Commands::Use {
             starter_identifier,
             dest,
-        } => commands::r#use::r#use(config, &starter_identifier, dest.as_deref()),
-        Commands::Find { search_term, json } => commands::find::find(config, &search_term, json),
+            vars,
+            mode,
+            refresh,
+            instance,
+            on_conflict,
+            dry_run,
+            json,
+            no_manifest,
+            no_hooks,
+            trust,
+            with,
+            open,
+        } => {
+            let options = UseOptions {
+                vars,
+                mode,
+                refresh,
+                instance,
+                on_conflict,
+                dry_run,
+                json,
+                no_manifest,
+                no_hooks,
+                trust,
+                with,
+                open,
+            };
+            commands::r#use::r#use(config, &starter_identifier, dest.as_deref(), &options)
+        }
+        Commands::Upgrade {
+            starter_identifier,
+            dir,
+            vars,
+            mode,
+            refresh,
+            instance,
+            trust,
+        } => {
+            let options = UseOptions {
+                vars,
+                mode,
+                refresh,
+                instance,
+                trust,
+                ..Default::default()
+            };
+            commands::upgrade::upgrade(config, &starter_identifier, dir.as_deref(), &options)
+        }
+        Commands::Diff {
+            starter_identifier,
+            dir,
+            stat,
+            vars,
+            mode,
+            refresh,
+            instance,
+            trust,
+        } => {
+            let options = UseOptions {
+                vars,
+                mode,
+                refresh,
+                instance,
+                trust,
+                ..Default::default()
+            };
+            commands::diff::diff(
+                config,
+                starter_identifier.as_deref(),
+                dir.as_deref(),
+                stat,
+                &options,
+            )
+        }
+        Commands::Find {
+            search_term, json, ..
+        } => commands::find::find(config, &search_term, json),
+        Commands::List {
+            instance,
+            group,
+            format,
+            ..
+        } => commands::list::list(config, instance.as_deref(), group.as_deref(), format),
         Commands::Storybook(storybook_command) => match storybook_command {
             StorybookCommands::Dev {
                 instance_path,
                 port,
+                ..
             } => commands::storybook::dev(config, instance_path.as_deref(), port),
             StorybookCommands::Prod {
                 instance_path,
                 output,
+                ..
             } => commands::storybook::prod(config, instance_path.as_deref(), output),
         },
-        Commands::UpdateReadme { instance_path } => {
+        Commands::Cache(cache_command) => match cache_command {
+            CacheCommands::List { json } => commands::cache::list(config, json),
+            CacheCommands::Path {} => commands::cache::path(config),
+            CacheCommands::Clear { sources } => commands::cache::clear(config, &sources),
+            CacheCommands::Prune { older_than } => commands::cache::prune(config, older_than),
+        },
+        Commands::UpdateReadme { instance_path, .. } => {
             commands::update_readme::update_readme(config, instance_path.as_deref())
         }
-        Commands::Config {} => {
-            unreachable!("Config command should be handled separately")
+        Commands::Validate {
+            instance_path,
+            json,
+            ..
+        } => commands::validate::validate(config, instance_path.as_deref(), json),
+        Commands::Config {} | Commands::Schema {} => {
+            unreachable!("Config and schema commands should be handled separately")
         }
     }
 }
@@ -178,9 +467,15 @@ fn execute_config_dependent_command(
 fn main() {
     let args = Cli::parse();
     setup_logger(args.verbose);
+    // Keep progress messages out of JSON output
+    if let Commands::Use { json: true, .. } = args.command
+        && !args.verbose
+    {
+        set_max_level(LevelFilter::Warn);
+    }
 
     if let Err(err) = handle_command(args.command) {
-        error!("Error: {}", err);
+        error!("Error: {:#}", err);
         std::process::exit(1);
     }
 }
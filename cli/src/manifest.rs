@@ -0,0 +1,121 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the manifest file `use` writes into projects, unless passed `--no-manifest`
+pub const MANIFEST_FILE_NAME: &str = ".jump-start.json";
+
+/// Hashes of files, keyed by their path relative to the project directory
+pub type FileHashes = BTreeMap<String, String>;
+
+/// Records which starter a project was created from
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct Manifest {
+    /// The starter identifier passed to `use`
+    pub starter: String,
+    /// Name of the instance local starters came from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    /// Repository remote starters came from, e.g. "github/kevinschaul/jump-start"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
+    /// Branch, tag or commit remote starters were pinned to, or "HEAD"
+    #[serde(default, rename = "ref", skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+    /// Commit the starter was resolved to, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Template variables passed to `use` with --var
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variables: BTreeMap<String, String>,
+    /// Optional features selected with --with
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
+    /// Unix timestamp (seconds) of the `use`
+    pub created_at: u64,
+    /// Unix timestamp (seconds) of the last `upgrade`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upgraded_at: Option<u64>,
+    /// Hash of each file written, e.g. "sha256:2c26b4...". After an `upgrade`, the hashes of the
+    /// starter's files at that time
+    pub files: FileHashes,
+}
+
+impl Manifest {
+    /// Path of the manifest in the project directory `dir`
+    pub fn path_in(dir: &Path) -> PathBuf {
+        dir.join(MANIFEST_FILE_NAME)
+    }
+
+    /// Load the manifest from the project directory `dir`, if it has one
+    pub fn load(dir: &Path) -> Result<Option<Self>> {
+        let path = Self::path_in(dir);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let contents = fs::read_to_string(&path)?;
+        let manifest = serde_json::from_str(&contents)
+            .with_context(|| format!("Could not parse manifest {:?}", path))?;
+        Ok(Some(manifest))
+    }
+
+    /// The manifest as written to .jump-start.json
+    pub fn to_json(&self) -> Result<String> {
+        let mut contents = serde_json::to_string_pretty(self)?;
+        contents.push('\n');
+        Ok(contents)
+    }
+
+    /// Write the manifest into the project directory `dir`
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        fs::write(Self::path_in(dir), self.to_json()?)?;
+        Ok(())
+    }
+}
+
+/// Hash file contents for the manifest, as "sha256:<hex>"
+///
+/// ```
+/// use jump_start::manifest::hash_contents;
+///
+/// assert_eq!(
+///     hash_contents(b"foo"),
+///     "sha256:2c26b46b68ffc68ff99b453c1d30413413422d706483bfa0f98a5e886266e7ae"
+/// );
+/// ```
+pub fn hash_contents(contents: &[u8]) -> String {
+    format!("sha256:{:x}", Sha256::digest(contents))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_manifest_roundtrip() -> Result<()> {
+        let temp_dir = tempdir()?;
+        assert_eq!(Manifest::load(temp_dir.path())?, None);
+
+        let manifest = Manifest {
+            starter: "@user/repo/group/name@v1".to_string(),
+            repository: Some("github/user/repo".to_string()),
+            git_ref: Some("v1".to_string()),
+            commit: Some("a".repeat(40)),
+            created_at: 1_700_000_000,
+            files: FileHashes::from([("src/main.rs".to_string(), hash_contents(b"fn main() {}"))]),
+            ..Default::default()
+        };
+        manifest.save(temp_dir.path())?;
+
+        let contents = fs::read_to_string(temp_dir.path().join(".jump-start.json"))?;
+        assert!(contents.contains("\"ref\": \"v1\""));
+        assert!(!contents.contains("instance"));
+        assert_eq!(Manifest::load(temp_dir.path())?, Some(manifest));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,300 @@
+use crate::starter::StarterConfig;
+use jsonschema::Validator;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::Marker;
+
+/// The JSON Schema of jump-start.yaml files, generated from [`StarterConfig`]
+///
+/// ```
+/// let schema = jump_start::schema::starter_config_schema();
+/// assert_eq!(schema["properties"]["mainFile"]["type"][0], "string");
+/// ```
+pub fn starter_config_schema() -> Value {
+    schemars::schema_for!(StarterConfig).to_value()
+}
+
+/// A problem with a jump-start.yaml file, at a 1-based line and column
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+/// The problems [`check_starter_config`] found in a jump-start.yaml file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigCheck {
+    /// Values that don't match the schema, or a single error if the YAML doesn't parse. The
+    /// file can't be read as a [`StarterConfig`] with any of these
+    pub errors: Vec<SchemaError>,
+    /// Keys the schema doesn't allow, which are most likely typos. Reading the file as a
+    /// [`StarterConfig`] ignores them
+    pub unknown_keys: Vec<SchemaError>,
+}
+
+/// Check the jump-start.yaml `contents` against [`starter_config_schema`], returning every
+/// problem found
+///
+/// ```
+/// use jump_start::schema::check_starter_config;
+///
+/// assert_eq!(check_starter_config("description: A starter\n"), Default::default());
+///
+/// let check = check_starter_config("description: A starter\nvariables:\n  - name: 3\n");
+/// assert_eq!(check.errors.len(), 1);
+/// assert_eq!((check.errors[0].line, check.errors[0].column), (3, 11));
+/// assert!(check.errors[0].message.starts_with("/variables/0/name: 3 is not of type"));
+///
+/// let check = check_starter_config("mainfile: index.js\nhooks:\n  postUse: [npm install]\n");
+/// assert!(check.errors.is_empty());
+/// let messages: Vec<&str> = check.unknown_keys.iter().map(|e| e.message.as_str()).collect();
+/// assert_eq!(messages, vec!["Unknown key \"mainfile\"", "Unknown key \"postUse\" in /hooks"]);
+/// assert_eq!((check.unknown_keys[1].line, check.unknown_keys[1].column), (3, 3));
+/// ```
+pub fn check_starter_config(contents: &str) -> ConfigCheck {
+    static SCHEMA: OnceLock<Value> = OnceLock::new();
+    static VALIDATOR: OnceLock<Validator> = OnceLock::new();
+
+    let instance: Value = match serde_yaml::from_str(contents) {
+        // Empty files are empty configs
+        Ok(Value::Null) => Value::Object(Default::default()),
+        Ok(instance) => instance,
+        Err(e) => {
+            let (line, column) = e
+                .location()
+                .map(|l| (l.line(), l.column()))
+                .unwrap_or((1, 1));
+            let error = SchemaError {
+                line,
+                column,
+                message: e.to_string(),
+            };
+            return ConfigCheck {
+                errors: vec![error],
+                unknown_keys: Vec::new(),
+            };
+        }
+    };
+
+    let schema = SCHEMA.get_or_init(starter_config_schema);
+    let validator = VALIDATOR.get_or_init(|| {
+        jsonschema::validator_for(schema).expect("The generated schema is a valid JSON Schema")
+    });
+    let locations = yaml_locations(contents);
+    let locate = |pointer: &str| locations.get(pointer).copied().unwrap_or((1, 1));
+    let evaluation = validator.evaluate(&instance);
+    let mut errors = Vec::new();
+    let mut unknown_keys = Vec::new();
+    for e in evaluation.iter_errors() {
+        // Optional fields allow null in an `anyOf`. Report why the value didn't match the other
+        // branch, rather than that it matched neither or isn't null
+        if e.schema_location.ends_with("/anyOf")
+            || (e.schema_location.contains("/anyOf/")
+                && e.error.to_string().ends_with("is not of type \"null\""))
+        {
+            continue;
+        }
+
+        let pointer = e.instance_location.as_str();
+        if let Some(schema_pointer) = e.schema_location.strip_suffix("/additionalProperties") {
+            // The error only describes the keys, so compare the object with the schema's
+            // properties to find them
+            let allowed = schema
+                .pointer(&format!("{}/properties", schema_pointer))
+                .and_then(Value::as_object);
+            let object = instance.pointer(pointer).and_then(Value::as_object);
+            let unexpected = object
+                .iter()
+                .flat_map(|o| o.keys())
+                .filter(|key| !allowed.is_some_and(|a| a.contains_key(*key)));
+            for key in unexpected {
+                let (line, column) = locate(&format!("{}/{}", pointer, escape_pointer(key)));
+                let message = if pointer.is_empty() {
+                    format!("Unknown key {:?}", key)
+                } else {
+                    format!("Unknown key {:?} in {}", key, pointer)
+                };
+                unknown_keys.push(SchemaError {
+                    line,
+                    column,
+                    message,
+                });
+            }
+            continue;
+        }
+
+        let (line, column) = locate(pointer);
+        let message = if pointer.is_empty() {
+            e.error.to_string()
+        } else {
+            format!("{}: {}", pointer, e.error)
+        };
+        errors.push(SchemaError {
+            line,
+            column,
+            message,
+        });
+    }
+    errors.sort_by_key(|e| (e.line, e.column));
+    unknown_keys.sort_by_key(|e| (e.line, e.column));
+    ConfigCheck {
+        errors,
+        unknown_keys,
+    }
+}
+
+/// Escape `key` for use as a JSON pointer segment
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Where each node of the YAML `contents` starts, keyed by JSON pointer. Mappings and sequences
+/// that are values in a mapping are located at their key
+fn yaml_locations(contents: &str) -> HashMap<String, (usize, usize)> {
+    let mut receiver = LocationReceiver::default();
+    // Anything that doesn't parse has already been reported by serde_yaml
+    let _ = Parser::new_from_str(contents).load(&mut receiver, false);
+    receiver.locations
+}
+
+/// Collections being walked, with the pointer of each
+enum Frame {
+    /// A mapping, with the key whose value comes next and where it is, if a key has been read
+    Mapping(String, Option<(String, Marker)>),
+    /// A sequence, with the index of the next item
+    Sequence(String, usize),
+}
+
+#[derive(Default)]
+struct LocationReceiver {
+    stack: Vec<Frame>,
+    locations: HashMap<String, (usize, usize)>,
+}
+
+impl LocationReceiver {
+    /// The pointer of the node that starts next, or `None` if it is a mapping key. `scalar` is
+    /// the node's value, if it is a scalar
+    fn next_pointer(&mut self, scalar: Option<&str>, mut mark: Marker) -> Option<String> {
+        let pointer = match self.stack.last_mut() {
+            None => String::new(),
+            Some(Frame::Mapping(pointer, pending)) => match pending.take() {
+                Some((key, key_mark)) => {
+                    // Block collections start where their first item does, so point at the key
+                    if scalar.is_none() {
+                        mark = key_mark;
+                    }
+                    format!("{}/{}", pointer, key)
+                }
+                None => {
+                    let key = escape_pointer(scalar.unwrap_or_default());
+                    *pending = Some((key, mark));
+                    return None;
+                }
+            },
+            Some(Frame::Sequence(pointer, index)) => {
+                *index += 1;
+                format!("{}/{}", pointer, *index - 1)
+            }
+        };
+        self.locations
+            .entry(pointer.clone())
+            .or_insert((mark.line(), mark.col() + 1));
+        Some(pointer)
+    }
+}
+
+impl MarkedEventReceiver for LocationReceiver {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        match event {
+            Event::Scalar(value, ..) => {
+                self.next_pointer(Some(&value), mark);
+            }
+            Event::Alias(_) => {
+                self.next_pointer(None, mark);
+            }
+            Event::MappingStart(..) => {
+                let pointer = self.next_pointer(None, mark).unwrap_or_default();
+                self.stack.push(Frame::Mapping(pointer, None));
+            }
+            Event::SequenceStart(..) => {
+                let pointer = self.next_pointer(None, mark).unwrap_or_default();
+                self.stack.push(Frame::Sequence(pointer, 0));
+            }
+            Event::MappingEnd | Event::SequenceEnd => {
+                self.stack.pop();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_starter_config() {
+        let contents = "\
+description: A starter
+defaultDir: [not, a, path]
+variables:
+  - name: title
+    type: text
+hooks:
+  post_use: npm install
+";
+        let errors = check_starter_config(contents).errors;
+        let found: Vec<(usize, usize, &str)> = errors
+            .iter()
+            .map(|e| {
+                (
+                    e.line,
+                    e.column,
+                    e.message.split(':').next().unwrap_or_default(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (2, 1, "/defaultDir"),
+                (5, 11, "/variables/0/type"),
+                (7, 13, "/hooks/post_use"),
+            ],
+            "{:?}",
+            errors
+        );
+
+        let errors = check_starter_config("description: [unclosed\n").errors;
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 2);
+
+        assert_eq!(check_starter_config(""), ConfigCheck::default());
+    }
+
+    #[test]
+    fn test_published_schema_is_up_to_date() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../jump-start.schema.json");
+        let published: Value =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(
+            published,
+            starter_config_schema(),
+            "Regenerate it with `jump-start schema > jump-start.schema.json`"
+        );
+    }
+}
@@ -1,23 +1,71 @@
 use crate::LocalStarterGroupLookup;
-use glob::glob;
+use crate::filter::{StarterFilter, walk_instance};
+use crate::schema::{SchemaError, check_starter_config};
+use glob::{MatchOptions, Pattern};
 use log::debug;
-use log::error;
+use log::{error, warn};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_yaml;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::io;
 use std::path::Path;
 use std::path::PathBuf;
 use std::str::FromStr;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 pub struct PreviewConfig {
     pub template: Option<String>,
     pub dependencies: Option<HashMap<String, String>>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// The type of a template variable, used to coerce values supplied on the command line
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum VariableType {
+    #[default]
+    String,
+    Boolean,
+    Number,
+}
+
+/// A template variable declared in a starter's jump-start.yaml file
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct StarterVariable {
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub var_type: VariableType,
+    pub default: Option<serde_json::Value>,
+    pub description: Option<String>,
+}
+
+/// Commands a starter runs when it is used
+#[derive(Debug, Serialize, Deserialize, Clone, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct StarterHooks {
+    /// Shell commands run in order in the destination directory after the files are copied,
+    /// e.g. "npm install"
+    #[serde(default)]
+    pub post_use: Vec<String>,
+}
+
+/// Files of a starter that are only copied when the feature is selected with `--with`
+#[derive(Debug, Serialize, Deserialize, Clone, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct StarterFeature {
+    pub description: Option<String>,
+    /// Paths or glob patterns relative to the starter. Directories include everything in them
+    #[serde(default)]
+    pub files: Vec<String>,
+}
+
+/// Configuration of a starter, read from the jump-start.yaml file in its directory
+#[derive(Debug, Serialize, Deserialize, Clone, Default, JsonSchema)]
+// Unknown keys are ignored when reading, but editors should flag them as likely typos
+#[schemars(title = "jump-start.yaml", deny_unknown_fields)]
 pub struct StarterConfig {
     pub description: Option<String>,
     #[serde(rename = "defaultDir")]
@@ -25,6 +73,78 @@ pub struct StarterConfig {
     #[serde(rename = "mainFile")]
     pub main_file: Option<String>,
     pub preview: Option<PreviewConfig>,
+    /// Variables rendered into file contents and file names when the starter is used
+    pub variables: Option<Vec<StarterVariable>>,
+    /// Commands run when the starter is used
+    pub hooks: Option<StarterHooks>,
+    /// Markdown printed after the starter is used, e.g. how to run the project
+    pub instructions: Option<String>,
+    /// Optional groups of files, keyed by the feature name passed to `--with`
+    pub features: Option<BTreeMap<String, StarterFeature>>,
+    /// Only copy files matching these patterns (.gitignore syntax)
+    pub include: Option<Vec<String>>,
+    /// Never copy files matching these patterns (.gitignore syntax), in addition to the ones
+    /// in .jumpstartignore
+    pub exclude: Option<Vec<String>>,
+}
+
+impl StarterConfig {
+    /// Names of the features whose files include `path`, given relative to the starter with
+    /// forward slashes. Files no feature lists are always copied
+    ///
+    /// ```
+    /// use jump_start::starter::StarterConfig;
+    ///
+    /// let config: StarterConfig = "
+    /// features:
+    ///   docker:
+    ///     files: [Dockerfile, .docker/]
+    ///   tests:
+    ///     files: ['tests/**/*.py']
+    /// "
+    /// .parse()
+    /// .unwrap();
+    /// assert_eq!(config.features_for("Dockerfile"), vec!["docker"]);
+    /// assert_eq!(config.features_for(".docker/compose.yml"), vec!["docker"]);
+    /// assert_eq!(config.features_for("tests/unit/test_main.py"), vec!["tests"]);
+    /// assert!(config.features_for("main.py").is_empty());
+    /// ```
+    pub fn features_for(&self, path: &str) -> Vec<&str> {
+        self.features
+            .iter()
+            .flatten()
+            .filter(|(_, feature)| {
+                feature
+                    .files
+                    .iter()
+                    .any(|pattern| path_matches(pattern, path))
+            })
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+}
+
+/// Whether the glob `pattern` matches `path` or one of the directories it is in
+fn path_matches(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+    let Ok(pattern) = Pattern::new(pattern) else {
+        return false;
+    };
+    let options = MatchOptions {
+        require_literal_separator: true,
+        ..Default::default()
+    };
+
+    let mut prefix = path;
+    loop {
+        if pattern.matches_with(prefix, options) {
+            return true;
+        }
+        match prefix.rsplit_once('/') {
+            Some((parent, _)) => prefix = parent,
+            None => return false,
+        }
+    }
 }
 
 impl FromStr for StarterConfig {
@@ -39,6 +159,9 @@ impl FromStr for StarterConfig {
 pub struct LocalStarterFile {
     pub path: String,
     pub contents: String,
+    /// Features the file belongs to. Files with features are optional, only copied `--with` one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
 }
 
 /// A string idenfitying a starter. Takes the form "[INSTANCE]/GROUP/NAME", where INSTANCE, if
@@ -46,24 +169,48 @@ pub struct LocalStarterFile {
 // pub type StarterIdentifier = String;
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RemoteStarter {
+    /// Name of the host to download from, e.g. "gitlab". Defaults to the default host
+    #[serde(default)]
+    pub host: Option<String>,
+    /// Owner of the repository. Named for GitHub, but used for every host
     pub github_username: String,
     pub github_repo: String,
+    /// Group the starter is in. Nested groups are separated by '/', e.g. "frontend/react"
     pub group: String,
     pub name: String,
+    /// Branch, tag or commit to use. Defaults to the repository's HEAD
+    #[serde(default)]
+    pub git_ref: Option<String>,
 }
 
 impl RemoteStarter {
     pub fn new(github_username: &str, github_repo: &str, group: &str, name: &str) -> Self {
         Self {
+            host: None,
             github_username: github_username.to_string(),
             github_repo: github_repo.to_string(),
             group: group.to_string(),
             name: name.to_string(),
+            git_ref: None,
         }
     }
 
+    /// Whether `identifier` refers to a remote starter rather than a local one
+    ///
+    /// ```
+    /// use jump_start::RemoteStarter;
+    /// assert!(RemoteStarter::is_remote_identifier("@kevinschaul/react-d3/Chart"));
+    /// assert!(RemoteStarter::is_remote_identifier("gitlab:@kevinschaul/react-d3/Chart"));
+    /// assert!(!RemoteStarter::is_remote_identifier("react-d3/Chart"));
+    /// ```
+    pub fn is_remote_identifier(identifier: &str) -> bool {
+        split_host_prefix(identifier).1.starts_with('@')
+    }
+
     /// A string idenfitying a starter. Takes the following form:
-    /// @GITHUB_USERNAME/[GITHUB_REPO]/GROUP/NAME
+    /// [HOST:]@GITHUB_USERNAME/[GITHUB_REPO]/GROUP/NAME[@REF|#COMMIT]
+    ///
+    /// `GROUP` may be nested, e.g. "frontend/react", in which case `GITHUB_REPO` must be given.
     ///
     /// # Examples
     ///
@@ -76,6 +223,7 @@ impl RemoteStarter {
     /// assert_eq!(starter.github_repo, "jump-start");
     /// assert_eq!(starter.group, "react-d3");
     /// assert_eq!(starter.name, "Chart");
+    /// assert_eq!(starter.git_ref, None);
     /// ```
     ///
     /// ```
@@ -87,6 +235,37 @@ impl RemoteStarter {
     /// assert_eq!(starter.name, "Chart");
     /// ```
     ///
+    /// ```
+    /// use jump_start::RemoteStarter;
+    /// let starter = RemoteStarter::from_path("@kevinschaul/jump-start/frontend/react/dashboard").unwrap();
+    /// assert_eq!(starter.github_repo, "jump-start");
+    /// assert_eq!(starter.group, "frontend/react");
+    /// assert_eq!(starter.name, "dashboard");
+    /// ```
+    ///
+    /// A branch or tag can be pinned with `@`, and a commit with `#`
+    ///
+    /// ```
+    /// use jump_start::RemoteStarter;
+    /// let starter = RemoteStarter::from_path("@kevinschaul/starters/react-d3/Chart@v1.2").unwrap();
+    /// assert_eq!(starter.name, "Chart");
+    /// assert_eq!(starter.git_ref.as_deref(), Some("v1.2"));
+    ///
+    /// let starter = RemoteStarter::from_path("@kevinschaul/react-d3/Chart#b3c8d93").unwrap();
+    /// assert_eq!(starter.name, "Chart");
+    /// assert_eq!(starter.git_ref.as_deref(), Some("b3c8d93"));
+    /// ```
+    ///
+    /// Other hosts are chosen with a prefix
+    ///
+    /// ```
+    /// use jump_start::RemoteStarter;
+    /// let starter = RemoteStarter::from_path("gitlab:@kevinschaul/starters/react-d3/Chart").unwrap();
+    /// assert_eq!(starter.host.as_deref(), Some("gitlab"));
+    /// assert_eq!(starter.github_username, "kevinschaul");
+    /// assert_eq!(starter.github_repo, "starters");
+    /// ```
+    ///
     /// ```should_panic
     /// use jump_start::RemoteStarter;
     /// let starter = RemoteStarter::from_path("react-d3/Chart").unwrap();
@@ -96,30 +275,112 @@ impl RemoteStarter {
     /// use jump_start::RemoteStarter;
     /// let starter = RemoteStarter::from_path("@kevinschaul/Chart").unwrap();
     /// ```
+    ///
+    /// ```should_panic
+    /// use jump_start::RemoteStarter;
+    /// let starter = RemoteStarter::from_path("@kevinschaul/react-d3/Chart#not-a-sha").unwrap();
+    /// ```
+    ///
+    /// ```should_panic
+    /// use jump_start::RemoteStarter;
+    /// let starter = RemoteStarter::from_path("@kevinschaul/react-d3/Chart@--upload-pack=touch").unwrap();
+    /// ```
     pub fn from_path(path: &str) -> Option<Self> {
+        let (host, path) = split_host_prefix(path);
+        // Trim off the leading '@' character, then split off the ref
+        let path = path.strip_prefix('@')?;
+        let (path, git_ref) = if let Some((path, git_ref)) = path.split_once('#') {
+            if !is_commit_sha(git_ref) {
+                return None;
+            }
+            (path, Some(git_ref))
+        } else if let Some((path, git_ref)) = path.split_once('@') {
+            // Refs are passed to git, which would read a leading '-' as an option
+            if git_ref.is_empty() || git_ref.starts_with('-') {
+                return None;
+            }
+            (path, Some(git_ref))
+        } else {
+            (path, None)
+        };
+
         let parts: Vec<&str> = path.split('/').collect();
-        // Trim off the leading '@' character
-        let github_username = &parts[0][1..];
+        let github_username = parts[0];
 
-        match parts.len() {
+        let mut starter = match parts.len() {
             3 => {
                 let github_repo = "jump-start";
-                Some(Self::new(github_username, github_repo, parts[1], parts[2]))
+                Self::new(github_username, github_repo, parts[1], parts[2])
             }
-            4 => {
+            n if n >= 4 => {
                 let github_repo = parts[1];
-                Some(Self::new(github_username, github_repo, parts[2], parts[3]))
+                let group = parts[2..n - 1].join("/");
+                Self::new(github_username, github_repo, &group, parts[n - 1])
             }
-            _ => panic!("Could not parse remote starter from string {:?}", path),
+            _ => return None,
+        };
+        starter.host = host.map(String::from);
+        starter.git_ref = git_ref.map(String::from);
+        Some(starter)
+    }
+
+    /// The ref to download: the pinned branch, tag or commit, or "HEAD"
+    pub fn archive_ref(&self) -> &str {
+        self.git_ref.as_deref().unwrap_or("HEAD")
+    }
+
+    /// Whether the starter is pinned to a full commit SHA, which can never change
+    pub fn is_pinned_to_commit(&self) -> bool {
+        self.git_ref
+            .as_deref()
+            .is_some_and(|r| r.len() == 40 && is_commit_sha(r))
+    }
+
+    /// Name of the cache directory for this starter's repository. Each pinned ref gets its own
+    /// directory, e.g. "starters@v1.2"
+    ///
+    /// ```
+    /// use jump_start::RemoteStarter;
+    /// let starter = RemoteStarter::from_path("@kevinschaul/starters/react-d3/Chart").unwrap();
+    /// assert_eq!(starter.cache_dir_name(), "starters");
+    ///
+    /// let starter = RemoteStarter::from_path("@kevinschaul/starters/react-d3/Chart@feature/x").unwrap();
+    /// assert_eq!(starter.cache_dir_name(), "starters@feature%2Fx");
+    /// ```
+    pub fn cache_dir_name(&self) -> String {
+        match &self.git_ref {
+            Some(git_ref) => format!("{}@{}", self.github_repo, git_ref.replace('/', "%2F")),
+            None => self.github_repo.clone(),
+        }
+    }
+}
+
+/// Split a "HOST:" prefix off a remote starter identifier
+fn split_host_prefix(identifier: &str) -> (Option<&str>, &str) {
+    match identifier.split_once(":@") {
+        Some((host, _))
+            if !host.is_empty()
+                && host
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c)) =>
+        {
+            (Some(host), &identifier[host.len() + 1..])
         }
+        _ => (None, identifier),
     }
 }
 
+/// Whether `s` looks like a (possibly abbreviated) git commit SHA
+fn is_commit_sha(s: &str) -> bool {
+    (7..=40).contains(&s.len()) && s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct LocalStarter {
     /// Full path identifier (group/name)
     pub path: String,
-    /// Group or category this starter belongs to
+    /// Group or category this starter belongs to. Nested groups are separated by '/', e.g.
+    /// "frontend/react"
     pub group: String,
     /// Name of this starter within its group
     pub name: String,
@@ -139,95 +400,218 @@ impl LocalStarter {
         }
     }
 
-    /// Parse a path (group/name) into a Starter
+    /// Parse a path (group/name) into a Starter. The group may be nested
+    ///
+    /// ```
+    /// use jump_start::LocalStarter;
+    /// let starter = LocalStarter::from_path("frontend/react/dashboard").unwrap();
+    /// assert_eq!(starter.group, "frontend/react");
+    /// assert_eq!(starter.name, "dashboard");
+    ///
+    /// assert!(LocalStarter::from_path("dashboard").is_none());
+    /// assert!(LocalStarter::from_path("frontend//dashboard").is_none());
+    /// ```
     pub fn from_path(path: &str) -> Option<Self> {
-        let parts: Vec<&str> = path.split('/').collect();
-        if parts.len() != 2 {
+        if path.split('/').any(str::is_empty) {
             return None;
         }
+        let (group, name) = path.rsplit_once('/')?;
 
-        Some(Self::new(parts[0], parts[1]))
+        Some(Self::new(group, name))
+    }
+
+    /// The starter in `starter_dir`, named after its path in the instance at `instance_dir`.
+    /// Every directory between the instance and the starter is a level of its group, so
+    /// starters directly in the instance, without a group, give `None`
+    pub fn from_dir(instance_dir: &Path, starter_dir: &Path) -> Option<Self> {
+        let components: Vec<String> = starter_dir
+            .strip_prefix(instance_dir)
+            .unwrap_or(starter_dir)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        let (name, group) = components.split_last()?;
+        if group.is_empty() {
+            return None;
+        }
+        Some(Self::new(&group.join("/"), name))
     }
 }
 
-pub fn parse_starters(path: &Path) -> io::Result<LocalStarterGroupLookup> {
+/// Find every starter in the instance at `instance_dir`, keyed by group.
+///
+/// jump-start.yaml files that don't match the schema or don't parse are skipped, and unknown keys
+/// and starters that collide with each other (see [`find_collisions`]) are reported as warnings.
+/// If `strict` is set, any of these is an error.
+pub fn parse_starters(instance_dir: &Path, strict: bool) -> io::Result<LocalStarterGroupLookup> {
     let mut groups: LocalStarterGroupLookup = HashMap::new();
+    let mut invalid = Vec::new();
 
-    // Define the glob pattern
-    let pattern = format!("{}/**/*jump-start.yaml", path.display());
-
-    // Use glob to find all matching files
-    for entry in glob(&pattern).expect("Failed to read glob pattern") {
+    // Walk the instance, skipping ignored directories, to find every jump-start.yaml
+    for entry in walk_instance(instance_dir) {
         match entry {
-            Ok(path) => {
-                // Skip files in node_modules and jump-start-tools
-                let path_str = path.to_string_lossy();
-                if path_str.contains("node_modules") || path_str.contains("jump-start-tools") {
+            Ok(entry) => {
+                if entry.file_name() != "jump-start.yaml"
+                    || !entry.file_type().is_some_and(|t| t.is_file())
+                {
                     continue;
                 }
+                let path = entry.path();
 
                 // Read and parse the YAML file
-                let file_content = fs::read_to_string(&path)?;
+                let file_content = fs::read_to_string(path)?;
                 debug!("Parsing YAML file: {}", path.display());
                 debug!("Content: {}", file_content);
 
+                // Check against the same schema editors use, to point at the problems
+                let check = check_starter_config(&file_content);
+                let located = |e: &SchemaError| {
+                    format!("{}:{}:{}: {}", path.display(), e.line, e.column, e.message)
+                };
+                // Unknown keys are ignored when reading the file, so they only stop the starter
+                // from being used in strict mode
+                for e in &check.unknown_keys {
+                    warn!("{}", located(e));
+                    if strict {
+                        invalid.push(located(e));
+                    }
+                }
+                if !check.errors.is_empty() {
+                    for e in &check.errors {
+                        error!("{}", located(e));
+                        invalid.push(located(e));
+                    }
+                    continue;
+                }
+
                 let starter_config = match file_content.parse::<StarterConfig>() {
                     Ok(config) => config,
                     Err(e) => {
-                        error!("Error parsing yaml for {}: {}", path.display(), e);
+                        let message = format!("Error parsing yaml for {}: {}", path.display(), e);
+                        error!("{}", message);
+                        invalid.push(message);
                         continue;
                     }
                 };
 
-                let current_dir = path.parent().unwrap();
-                let name = current_dir
-                    .file_name()
-                    .unwrap()
-                    .to_string_lossy()
-                    .to_string();
-                let group = current_dir
-                    .parent()
-                    .unwrap()
-                    .file_name()
-                    .unwrap()
-                    .to_string_lossy()
-                    .to_string();
-
-                let starter = LocalStarter {
-                    name: name.clone(),
-                    group: group.clone(),
-                    path: format!("{}/{}", group, name),
-                    config: Some(starter_config),
+                let Some(mut starter) =
+                    LocalStarter::from_dir(instance_dir, path.parent().unwrap())
+                else {
+                    error!(
+                        "Skipping {}: starters must be inside a group directory",
+                        path.display()
+                    );
+                    continue;
                 };
+                starter.config = Some(starter_config);
 
-                groups.entry(group).or_default().push(starter);
+                groups
+                    .entry(starter.group.clone())
+                    .or_default()
+                    .push(starter);
             }
-            Err(e) => error!("Error processing glob entry: {}", e),
+            Err(e) => error!("Error walking the instance: {}", e),
         }
     }
 
+    let collisions = find_collisions(instance_dir, &groups);
+    if strict && !(invalid.is_empty() && collisions.is_empty()) {
+        invalid.extend(collisions);
+        return Err(io::Error::other(invalid.join("\n")));
+    }
+    for collision in &collisions {
+        warn!("{}", collision);
+    }
+
     Ok(groups)
 }
 
+/// Describe starters in `groups` that can't be told apart or used on their own: identifiers
+/// that only differ in case, which collide on case-insensitive file systems, and starters inside
+/// other starters, which are copied along with them.
+///
+/// ```
+/// use jump_start::LocalStarter;
+/// use jump_start::starter::find_collisions;
+/// use std::collections::HashMap;
+/// use std::path::Path;
+///
+/// let mut groups = HashMap::new();
+/// for path in ["react/app", "React/app", "react/app/nested/child", "vue/app"] {
+///     let starter = LocalStarter::from_path(path).unwrap();
+///     groups.entry(starter.group.clone()).or_insert_with(Vec::new).push(starter);
+/// }
+/// let collisions = find_collisions(Path::new("/instance"), &groups);
+/// assert_eq!(collisions.len(), 3);
+/// assert!(collisions[0].contains("\"React/app\" and \"react/app\" differ only in case"));
+/// ```
+pub fn find_collisions(instance_dir: &Path, groups: &LocalStarterGroupLookup) -> Vec<String> {
+    let mut starters: Vec<&LocalStarter> = groups.values().flatten().collect();
+    starters.sort_by(|a, b| a.path.cmp(&b.path));
+    let dir = |starter: &LocalStarter| instance_dir.join(&starter.path);
+    let is_inside = |inner: &str, outer: &str| {
+        inner
+            .to_lowercase()
+            .starts_with(&format!("{}/", outer.to_lowercase()))
+    };
+
+    let mut collisions = Vec::new();
+    for (i, a) in starters.iter().enumerate() {
+        for b in &starters[i + 1..] {
+            let (inner, outer) = if a.path.len() > b.path.len() {
+                (a, b)
+            } else {
+                (b, a)
+            };
+            if a.path.to_lowercase() == b.path.to_lowercase() {
+                collisions.push(format!(
+                    "Starters {:?} and {:?} differ only in case, so they collide on case-insensitive file systems ({:?} and {:?})",
+                    a.path,
+                    b.path,
+                    dir(a),
+                    dir(b)
+                ));
+            } else if is_inside(&inner.path, &outer.path) {
+                collisions.push(format!(
+                    "Starter {:?} is inside starter {:?}, so using {:?} copies it too ({:?} and {:?})",
+                    inner.path,
+                    outer.path,
+                    outer.path,
+                    dir(inner),
+                    dir(outer)
+                ));
+            }
+        }
+    }
+    collisions
+}
+
 // Get files for a starter using the instance directory as the base path
 pub fn get_starter_files(
     starter: &LocalStarter,
     instance_dir: &Path,
 ) -> io::Result<Vec<LocalStarterFile>> {
     let mut out = Vec::new();
-    let excluded_files = ["jump-start.yaml", "degit.json"];
 
     // Get the full path to the starter directory using the instance dir as base
     let starter_dir = instance_dir.join(&starter.group).join(&starter.name);
 
     if starter_dir.exists() && starter_dir.is_dir() {
+        // Preview the same files `use` would copy
+        let filter =
+            StarterFilter::new(&starter_dir, starter.config.as_ref()).map_err(io::Error::other)?;
         // Walk the directory recursively
-        visit_dirs(
-            &starter_dir,
-            &mut out,
-            &excluded_files,
-            &starter_dir.to_string_lossy(),
-        )?;
+        visit_dirs(&starter_dir, &mut out, &filter, &starter_dir)?;
+        // Mark files only copied `--with` a feature, so the preview can show them as optional
+        if let Some(config) = &starter.config {
+            for file in &mut out {
+                file.features = config
+                    .features_for(&file.path.replace('\\', "/"))
+                    .into_iter()
+                    .map(String::from)
+                    .collect();
+            }
+        }
         debug!(
             "Found {} files for starter {}/{}",
             out.len(),
@@ -241,6 +625,7 @@ pub fn get_starter_files(
         out.push(LocalStarterFile {
             path: "example.file".to_string(),
             contents: "// This is a sample file content\nconsole.log('Hello world');\n".to_string(),
+            features: vec![],
         });
     }
 
@@ -250,41 +635,35 @@ pub fn get_starter_files(
 fn visit_dirs(
     dir: &Path,
     files: &mut Vec<LocalStarterFile>,
-    excluded_files: &[&str],
-    base_path: &str,
+    filter: &StarterFilter,
+    base_path: &Path,
 ) -> io::Result<()> {
     if dir.is_dir() {
         for entry in fs::read_dir(dir)? {
             let entry = entry?;
             let path = entry.path();
-
-            if path.is_dir() {
-                visit_dirs(&path, files, excluded_files, base_path)?;
-            } else if let Some(file_name) = path.file_name() {
-                let file_name_str = file_name.to_string_lossy();
-
-                if !excluded_files.contains(&file_name_str.as_ref()) {
-                    // Get relative path
-                    let base = Path::new(base_path);
-                    let rel_path = match path.strip_prefix(base) {
-                        Ok(rel) => rel.to_string_lossy().to_string(),
-                        Err(_) => path.to_string_lossy().to_string(),
-                    };
-
-                    // Try to read file contents
-                    match fs::read_to_string(&path) {
-                        Ok(contents) => {
-                            // Create and push StarterFile
-                            files.push(LocalStarterFile {
-                                path: rel_path,
-                                contents,
-                            });
-                        }
-                        Err(e) => {
-                            error!("Warning: Could not read file {:?}: {}", path, e);
-                            // Try to handle binary files by reading as bytes
-                            // but for now just skip them
-                        }
+            let rel_path = path.strip_prefix(base_path).unwrap_or(&path);
+            let is_dir = path.is_dir();
+
+            if !filter.is_included(rel_path, is_dir) {
+                debug!("Leaving out {:?}", rel_path);
+            } else if is_dir {
+                visit_dirs(&path, files, filter, base_path)?;
+            } else {
+                // Try to read file contents
+                match fs::read_to_string(&path) {
+                    Ok(contents) => {
+                        // Create and push StarterFile
+                        files.push(LocalStarterFile {
+                            path: rel_path.to_string_lossy().to_string(),
+                            contents,
+                            features: vec![],
+                        });
+                    }
+                    Err(e) => {
+                        error!("Warning: Could not read file {:?}: {}", path, e);
+                        // Try to handle binary files by reading as bytes
+                        // but for now just skip them
                     }
                 }
             }
@@ -300,10 +679,17 @@ pub fn get_starter_command(
     github_repo: &str,
 ) -> String {
     if !github_username.is_empty() && !github_repo.is_empty() {
-        if github_repo == "jump-start" {
-            format!("jump-start use @{}/{}/{}", github_username, starter.group, starter.name)
+        // The repository can only be left out when the group isn't nested
+        if github_repo == "jump-start" && !starter.group.contains('/') {
+            format!(
+                "jump-start use @{}/{}/{}",
+                github_username, starter.group, starter.name
+            )
         } else {
-            format!("jump-start use @{}/{}/{}/{}", github_username, github_repo, starter.group, starter.name)
+            format!(
+                "jump-start use @{}/{}/{}/{}",
+                github_username, github_repo, starter.group, starter.name
+            )
         }
     } else {
         format!("jump-start use {}/{}", starter.group, starter.name)
@@ -0,0 +1,242 @@
+use crate::starter::{StarterVariable, VariableType};
+use anyhow::{Context, Result};
+use handlebars::Handlebars;
+use serde_json::{Map, Value};
+
+/// Variable values keyed by name, ready to be passed to the template engine
+pub type TemplateVariables = Map<String, Value>;
+
+/// Parse a `KEY=VALUE` pair, as passed to `--var`
+pub fn parse_key_value(s: &str) -> Result<(String, String)> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => anyhow::bail!("Invalid variable {:?}, expected KEY=VALUE", s),
+    }
+}
+
+/// Combine the variables declared by a starter with the values supplied by the user.
+///
+/// Supplied values win over declared defaults. Every declared variable must end up with a
+/// value, and every supplied value must belong to a declared variable.
+pub fn resolve_variables(
+    declared: &[StarterVariable],
+    supplied: &[(String, String)],
+) -> Result<TemplateVariables> {
+    let mut variables = TemplateVariables::new();
+
+    for (key, _) in supplied {
+        if !declared.iter().any(|v| &v.name == key) {
+            let names: Vec<&str> = declared.iter().map(|v| v.name.as_str()).collect();
+            anyhow::bail!(
+                "Starter does not declare variable {:?} (declared: {})",
+                key,
+                if names.is_empty() {
+                    "none".to_string()
+                } else {
+                    names.join(", ")
+                }
+            );
+        }
+    }
+
+    for variable in declared {
+        // The last value wins if a variable is supplied more than once
+        let supplied_value = supplied
+            .iter()
+            .rev()
+            .find(|(key, _)| key == &variable.name)
+            .map(|(_, value)| value);
+
+        let value = match (supplied_value, &variable.default) {
+            (Some(raw), _) => coerce_value(variable, raw)?,
+            (None, Some(Value::String(raw))) => coerce_value(variable, raw)?,
+            (None, Some(default)) => default.clone(),
+            (None, None) => anyhow::bail!(
+                "Missing value for variable {:?}{}. Pass it with --var {}=VALUE",
+                variable.name,
+                variable
+                    .description
+                    .as_ref()
+                    .map(|d| format!(" ({})", d.trim()))
+                    .unwrap_or_default(),
+                variable.name
+            ),
+        };
+
+        variables.insert(variable.name.clone(), value);
+    }
+
+    Ok(variables)
+}
+
+/// Convert a raw string into a JSON value according to the variable's declared type
+fn coerce_value(variable: &StarterVariable, raw: &str) -> Result<Value> {
+    match variable.var_type {
+        VariableType::String => Ok(Value::String(raw.to_string())),
+        VariableType::Boolean => match raw.to_lowercase().as_str() {
+            "true" | "yes" | "1" => Ok(Value::Bool(true)),
+            "false" | "no" | "0" | "" => Ok(Value::Bool(false)),
+            _ => anyhow::bail!(
+                "Variable {:?} expects a boolean, got {:?}",
+                variable.name,
+                raw
+            ),
+        },
+        VariableType::Number => {
+            let number: serde_json::Number = raw.parse().with_context(|| {
+                format!(
+                    "Variable {:?} expects a number, got {:?}",
+                    variable.name, raw
+                )
+            })?;
+            Ok(Value::Number(number))
+        }
+    }
+}
+
+/// Renders file contents and file names through Handlebars using a fixed set of variables
+pub struct TemplateRenderer {
+    handlebars: Handlebars<'static>,
+    variables: Value,
+}
+
+impl TemplateRenderer {
+    pub fn new(variables: TemplateVariables) -> Self {
+        let mut handlebars = Handlebars::new();
+        // Starter files are code, not HTML
+        handlebars.register_escape_fn(handlebars::no_escape);
+        // Fail loudly on typos rather than rendering an empty string
+        handlebars.set_strict_mode(true);
+
+        Self {
+            handlebars,
+            variables: Value::Object(variables),
+        }
+    }
+
+    pub fn render(&self, template: &str) -> Result<String> {
+        Ok(self.handlebars.render_template(template, &self.variables)?)
+    }
+
+    /// Render a single file or directory name. Names without template tags are returned as-is.
+    pub fn render_file_name(&self, name: &str) -> Result<String> {
+        if !name.contains("{{") {
+            return Ok(name.to_string());
+        }
+
+        let rendered = self.render(name)?;
+        if rendered.is_empty()
+            || rendered.contains(['/', '\\'])
+            || rendered == "."
+            || rendered == ".."
+        {
+            anyhow::bail!(
+                "File name {:?} rendered to invalid name {:?}",
+                name,
+                rendered
+            );
+        }
+        Ok(rendered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn variable(name: &str, var_type: VariableType, default: Option<Value>) -> StarterVariable {
+        StarterVariable {
+            name: name.to_string(),
+            var_type,
+            default,
+            description: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_key_value() {
+        assert_eq!(
+            parse_key_value("name=my-app").unwrap(),
+            ("name".to_string(), "my-app".to_string())
+        );
+        assert_eq!(
+            parse_key_value("title=a=b").unwrap(),
+            ("title".to_string(), "a=b".to_string())
+        );
+        assert!(parse_key_value("name").is_err());
+        assert!(parse_key_value("=value").is_err());
+    }
+
+    #[test]
+    fn test_resolve_variables_defaults_and_supplied() -> Result<()> {
+        let declared = vec![
+            variable("name", VariableType::String, None),
+            variable("docker", VariableType::Boolean, Some(json!(false))),
+            variable("port", VariableType::Number, Some(json!("8080"))),
+        ];
+        let supplied = vec![
+            ("name".to_string(), "my-app".to_string()),
+            ("docker".to_string(), "true".to_string()),
+        ];
+
+        let variables = resolve_variables(&declared, &supplied)?;
+
+        assert_eq!(variables["name"], json!("my-app"));
+        assert_eq!(variables["docker"], json!(true));
+        assert_eq!(variables["port"], json!(8080));
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_variables_errors() {
+        let declared = vec![variable("name", VariableType::String, None)];
+
+        let missing = resolve_variables(&declared, &[]).unwrap_err();
+        assert!(missing.to_string().contains("--var name=VALUE"));
+
+        let unknown = resolve_variables(
+            &declared,
+            &[
+                ("name".to_string(), "a".to_string()),
+                ("nmae".to_string(), "b".to_string()),
+            ],
+        )
+        .unwrap_err();
+        assert!(unknown.to_string().contains("\"nmae\""));
+
+        let declared = vec![variable("port", VariableType::Number, None)];
+        let invalid =
+            resolve_variables(&declared, &[("port".to_string(), "abc".to_string())]).unwrap_err();
+        assert!(invalid.to_string().contains("expects a number"));
+    }
+
+    #[test]
+    fn test_render() -> Result<()> {
+        let mut variables = TemplateVariables::new();
+        variables.insert("name".to_string(), json!("my-app"));
+        variables.insert("docker".to_string(), json!(false));
+        let renderer = TemplateRenderer::new(variables);
+
+        assert_eq!(
+            renderer.render("# {{name}}{{#if docker}} (docker){{/if}}")?,
+            "# my-app"
+        );
+        assert_eq!(renderer.render_file_name("{{name}}.py")?, "my-app.py");
+        assert_eq!(renderer.render_file_name("plain.py")?, "plain.py");
+        assert!(renderer.render("{{unknown}}").is_err());
+
+        // Names that would write outside of, or over, their directory are rejected
+        for value in ["", ".", "..", "a/b", "a\\b"] {
+            let mut variables = TemplateVariables::new();
+            variables.insert("name".to_string(), json!(value));
+            let renderer = TemplateRenderer::new(variables);
+            assert!(
+                renderer.render_file_name("{{name}}").is_err(),
+                "{:?}",
+                value
+            );
+        }
+        Ok(())
+    }
+}
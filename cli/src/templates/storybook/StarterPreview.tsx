@@ -30,6 +30,9 @@ export default function StarterPreview({
     const mainFile =
       files.find((d) => d.path === starter.config?.mainFile)?.path || files[0].path;
 
+    // Files of optional features are only copied with `--with`
+    const optionalFiles = files.filter((d) => d.features?.length);
+
     const filesForSandpack = files.reduce((p, v) => {
       // Rewrite files into ./starter directory to avoid conflicts with
       // codesandbox's "index.js"
@@ -79,6 +82,17 @@ export default function StarterPreview({
 
           <hr />
           <h3>Starter files</h3>
+          {optionalFiles.length > 0 && (
+            <p className="starter-optional-files">
+              Optional files:{" "}
+              {optionalFiles.map((d, i) => (
+                <React.Fragment key={d.path}>
+                  {i > 0 && ", "}
+                  <code>{d.path}</code> (<code>--with {d.features?.join(",")}</code>)
+                </React.Fragment>
+              ))}
+            </p>
+          )}
           <SandpackLayout>
             <SandpackFileExplorer
               autoHiddenFiles={true}
@@ -9,6 +9,8 @@ export type StarterFile = {
   // type: "dir" | "file";
   type: string;
   contents?: string;
+  // Optional features the file belongs to; it is only copied `--with` one of them
+  features?: string[];
 }
 
 export type Starter = {
@@ -20,6 +22,7 @@ export type Starter = {
     defaultDir?: string;
     mainFile?: string;
     preview?: StarterPreviewConfig;
+    features?: { [name: string]: { description?: string; files: string[] } };
   };
 };
 
@@ -0,0 +1,113 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use jump_start::Config;
+use jump_start::cache::{CacheMetadata, list_cache_entries, now_secs};
+use jump_start::commands::cache;
+use std::time::Duration;
+use tempfile::tempdir;
+
+/// Create a cached tarball for `owner/repo`, downloaded `age_secs` ago
+fn create_cache_entry(cache_dir: &Path, owner: &str, repo: &str, age_secs: u64) -> Result<()> {
+    let repo_dir = cache_dir.join("github").join(owner).join(repo);
+    fs::create_dir_all(repo_dir.join("group/starter"))?;
+    let tar_path = repo_dir.join("HEAD.tar.gz");
+    fs::write(&tar_path, "0123456789")?;
+    fs::write(repo_dir.join("group/starter/file.txt"), "hello")?;
+    CacheMetadata {
+        url: format!(
+            "https://www.github.com/{}/{}/archive/HEAD.tar.gz",
+            owner, repo
+        ),
+        fetched_at: now_secs() - age_secs,
+        ..Default::default()
+    }
+    .save(&tar_path)?;
+    Ok(())
+}
+
+fn test_config(cache_dir: &Path) -> Config {
+    Config {
+        cache_dir: Some(cache_dir.to_path_buf()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_list_cache_entries() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let cache_dir = temp_dir.path();
+    create_cache_entry(cache_dir, "kevinschaul", "jump-start", 60)?;
+    create_cache_entry(cache_dir, "alice", "starters", 3 * 86400)?;
+
+    let entries = list_cache_entries(cache_dir)?;
+
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].source, "github/alice/starters");
+    assert_eq!(entries[1].source, "github/kevinschaul/jump-start");
+    assert!(entries[0].age() >= Duration::from_secs(3 * 86400));
+    assert!(entries[1].size > 10);
+    assert_eq!(
+        entries[1].url.as_deref(),
+        Some("https://www.github.com/kevinschaul/jump-start/archive/HEAD.tar.gz")
+    );
+
+    // A missing cache directory is just empty
+    assert!(list_cache_entries(&cache_dir.join("missing"))?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_prune_cache() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let cache_dir = temp_dir.path();
+    create_cache_entry(cache_dir, "kevinschaul", "jump-start", 60)?;
+    create_cache_entry(cache_dir, "alice", "starters", 3 * 86400)?;
+
+    cache::prune(test_config(cache_dir), Duration::from_secs(86400))?;
+
+    let entries = list_cache_entries(cache_dir)?;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].source, "github/kevinschaul/jump-start");
+    // Empty owner directories are cleaned up too
+    assert!(!cache_dir.join("github/alice").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_clear_cache() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let cache_dir = temp_dir.path();
+    create_cache_entry(cache_dir, "kevinschaul", "jump-start", 60)?;
+    create_cache_entry(cache_dir, "alice", "starters", 60)?;
+    create_cache_entry(cache_dir, "alice", "other", 60)?;
+    create_cache_entry(cache_dir, "alice", "other@v1", 60)?;
+    create_cache_entry(cache_dir, "alice", "starters@v1", 60)?;
+    create_cache_entry(cache_dir, "alice", "starters@v2", 60)?;
+
+    // A pinned copy can be cleared on its own
+    cache::clear(test_config(cache_dir), &["alice/other@v1".to_string()])?;
+    assert!(!cache_dir.join("github/alice/other@v1").exists());
+    assert_eq!(list_cache_entries(cache_dir)?.len(), 5);
+
+    // Clearing a repository clears its pinned copies too
+    cache::clear(test_config(cache_dir), &["@alice/starters".to_string()])?;
+    let sources: Vec<String> = list_cache_entries(cache_dir)?
+        .into_iter()
+        .map(|e| e.source)
+        .collect();
+    assert_eq!(
+        sources,
+        vec!["github/alice/other", "github/kevinschaul/jump-start"]
+    );
+
+    assert!(cache::clear(test_config(cache_dir), &["nobody/nothing".to_string()]).is_err());
+
+    cache::clear(test_config(cache_dir), &[])?;
+    assert!(list_cache_entries(cache_dir)?.is_empty());
+
+    Ok(())
+}
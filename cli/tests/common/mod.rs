@@ -0,0 +1,52 @@
+//! Helpers shared by the integration tests. Each test crate uses a different subset of them.
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+use anyhow::Result;
+use jump_start::{Config, JumpStartInstance};
+
+/// A config with `instance_dir` as its only, default, instance
+pub fn config(instance_dir: &Path) -> Config {
+    Config {
+        instances: vec![JumpStartInstance {
+            name: "test-instance".to_string(),
+            path: instance_dir.to_path_buf(),
+            default: Some(true),
+        }],
+        ..Default::default()
+    }
+}
+
+/// Run git in `cwd` with a test identity, failing if it does
+pub fn git(args: &[&str], cwd: &Path) -> Result<()> {
+    let status = Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(cwd)
+        .status()?;
+    anyhow::ensure!(status.success(), "git {:?} failed", args);
+    Ok(())
+}
+
+/// Run the jump-start binary with `config` as its config file, in a home directory at `home`
+pub fn run_jump_start(home: &Path, config: &Config, args: &[&str]) -> Result<Output> {
+    // The config file is in the OS config directory, which follows HOME and XDG_CONFIG_HOME
+    for config_dir in [
+        ".config/jump-start",
+        "Library/Application Support/jump-start",
+    ] {
+        fs::create_dir_all(home.join(config_dir))?;
+        fs::write(
+            home.join(config_dir).join("config.json"),
+            serde_json::to_string(config)?,
+        )?;
+    }
+    Ok(Command::new(env!("CARGO_BIN_EXE_jump-start"))
+        .args(args)
+        .env("HOME", home)
+        .env("XDG_CONFIG_HOME", home.join(".config"))
+        .output()?)
+}
@@ -0,0 +1,73 @@
+use std::fs;
+
+use anyhow::Result;
+use jump_start::commands::diff::diff;
+use jump_start::commands::r#use::{self, UseOptions};
+use tempfile::tempdir;
+
+mod common;
+use common::config;
+
+#[test]
+fn test_diff_uses_manifest_starter() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let instance_dir = temp_dir.path().join("instance");
+    let starter_dir = instance_dir.join("group/starter");
+    let project_dir = temp_dir.path().join("project");
+    fs::create_dir_all(&starter_dir)?;
+    fs::write(
+        starter_dir.join("jump-start.yaml"),
+        "description: Test\nvariables:\n  - name: name\n    default: example\n",
+    )?;
+    fs::write(starter_dir.join("README.md"), "# {{name}}\n")?;
+
+    let dir = project_dir.to_str().unwrap();
+    r#use::r#use(
+        config(&instance_dir),
+        "group/starter",
+        Some(dir),
+        &UseOptions {
+            vars: vec![("name".to_string(), "my-project".to_string())],
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        fs::read_to_string(project_dir.join("README.md"))?,
+        "# my-project\n"
+    );
+
+    // The starter and variables come from the manifest
+    diff(
+        config(&instance_dir),
+        None,
+        Some(dir),
+        false,
+        &UseOptions::default(),
+    )?;
+    diff(
+        config(&instance_dir),
+        Some("group/starter"),
+        Some(dir),
+        true,
+        &UseOptions::default(),
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn test_diff_requires_starter_without_manifest() -> Result<()> {
+    let temp_dir = tempdir()?;
+
+    let err = diff(
+        config(temp_dir.path()),
+        None,
+        Some(temp_dir.path().to_str().unwrap()),
+        false,
+        &UseOptions::default(),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("Pass the starter"), "{}", err);
+
+    Ok(())
+}
@@ -32,7 +32,7 @@ fn test_search_instance_filename_group() -> Result<()> {
     let search_term = "group";
     let pattern = make_pattern(search_term)?;
 
-    let matches = search_instance(instance_dir, &pattern)?;
+    let matches = search_instance(instance_dir, &pattern, false)?;
     assert_eq!(matches.len(), 1);
     assert_eq!(matches[0].path, "group/test-starter");
 
@@ -46,7 +46,7 @@ fn test_search_instance_filename_name() -> Result<()> {
     let search_term = "starter";
     let pattern = make_pattern(search_term)?;
 
-    let matches = search_instance(instance_dir, &pattern)?;
+    let matches = search_instance(instance_dir, &pattern, false)?;
     assert_eq!(matches.len(), 1);
     assert_eq!(matches[0].path, "group/test-starter");
 
@@ -60,7 +60,7 @@ fn test_search_instance_filename_not_instance() -> Result<()> {
     let search_term = "instance";
     let pattern = make_pattern(search_term)?;
 
-    let matches = search_instance(instance_dir, &pattern)?;
+    let matches = search_instance(instance_dir, &pattern, false)?;
     assert_eq!(matches.len(), 0);
 
     Ok(())
@@ -73,7 +73,7 @@ fn test_search_instance_filename_group_name() -> Result<()> {
     let search_term = "group/test";
     let pattern = make_pattern(search_term)?;
 
-    let matches = search_instance(instance_dir, &pattern)?;
+    let matches = search_instance(instance_dir, &pattern, false)?;
     assert_eq!(matches.len(), 1);
     assert_eq!(matches[0].path, "group/test-starter");
 
@@ -87,7 +87,7 @@ fn test_search_instance_contents_yaml() -> Result<()> {
     let search_term = "A starter for testing";
     let pattern = make_pattern(search_term)?;
 
-    let matches = search_instance(instance_dir, &pattern)?;
+    let matches = search_instance(instance_dir, &pattern, false)?;
     assert_eq!(matches.len(), 1);
     assert_eq!(matches[0].path, "group/test-starter");
 
@@ -107,9 +107,107 @@ fn test_search_instance_with_symlink() -> Result<()> {
     let search_term = "A starter for testing";
     let pattern = make_pattern(search_term)?;
 
-    let matches = search_instance(instance_dir, &pattern)?;
+    let matches = search_instance(instance_dir, &pattern, false)?;
     assert_eq!(matches.len(), 1);
     assert_eq!(matches[0].path, "group/test-starter");
 
     Ok(())
 }
+
+#[test]
+fn test_search_instance_respects_ignore_files() -> Result<()> {
+    let temp_dir = fixture1()?;
+    let instance_dir = temp_dir.path().join("instance");
+    for starter_dir in [
+        "group/jump-start-tools-demo",
+        "group/node_modules/pkg",
+        "build/output",
+        "drafts/wip",
+    ] {
+        fs::create_dir_all(instance_dir.join(starter_dir))?;
+        fs::write(
+            instance_dir.join(starter_dir).join("jump-start.yaml"),
+            "description: A starter for testing\n",
+        )?;
+    }
+    fs::write(instance_dir.join(".gitignore"), "build/\n")?;
+    fs::write(instance_dir.join(".jumpstartignore"), "drafts/\n")?;
+
+    let pattern = make_pattern("A starter for testing")?;
+    let matches = search_instance(instance_dir, &pattern, false)?;
+    let mut paths: Vec<&str> = matches.iter().map(|s| s.path.as_str()).collect();
+    paths.sort();
+    assert_eq!(
+        paths,
+        vec!["group/jump-start-tools-demo", "group/test-starter"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_search_instance_nested_groups() -> Result<()> {
+    let temp_dir = fixture1()?;
+    let instance_dir = temp_dir.path().join("instance");
+    let starter_dir = instance_dir.join("frontend/react/dashboard");
+    fs::create_dir_all(&starter_dir)?;
+    fs::write(starter_dir.join("jump-start.yaml"), "description: Nested\n")?;
+
+    let pattern = make_pattern("react/dash")?;
+    let matches = search_instance(instance_dir, &pattern, false)?;
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].path, "frontend/react/dashboard");
+    assert_eq!(matches[0].group, "frontend/react");
+    assert_eq!(matches[0].name, "dashboard");
+
+    Ok(())
+}
+
+#[test]
+fn test_search_instance_colliding_starters() -> Result<()> {
+    let temp_dir = fixture1()?;
+    let instance_dir = temp_dir.path().join("instance");
+    let nested_dir = instance_dir.join("group/test-starter/examples/child");
+    fs::create_dir_all(&nested_dir)?;
+    fs::write(nested_dir.join("jump-start.yaml"), "description: Nested\n")?;
+
+    // Both starters are kept, with a warning
+    let pattern = make_pattern("test-starter")?;
+    let matches = search_instance(instance_dir.clone(), &pattern, false)?;
+    assert_eq!(matches.len(), 2);
+
+    let err = search_instance(instance_dir, &pattern, true).unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "Starter \"group/test-starter/examples/child\" is inside starter \"group/test-starter\""
+        ),
+        "{}",
+        err
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_search_instance_unparseable_starter() -> Result<()> {
+    let temp_dir = fixture1()?;
+    let instance_dir = temp_dir.path().join("instance");
+    let broken_dir = instance_dir.join("group/broken");
+    fs::create_dir_all(&broken_dir)?;
+    // Matches the schema, but serde rejects the duplicate key
+    fs::write(
+        broken_dir.join("jump-start.yaml"),
+        "description: One\ndescription: Two\n",
+    )?;
+
+    // The starter is skipped
+    let pattern = make_pattern("group")?;
+    let matches = search_instance(instance_dir.clone(), &pattern, false)?;
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].path, "group/test-starter");
+
+    let err = search_instance(instance_dir, &pattern, true).unwrap_err();
+    assert!(err.to_string().contains("duplicate field"), "{}", err);
+
+    Ok(())
+}
@@ -0,0 +1,233 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use jump_start::commands::list::{ListFormat, format_starters, list_starters};
+use jump_start::{Config, JumpStartInstance};
+use tempfile::tempdir;
+
+mod common;
+use common::run_jump_start;
+
+fn write_starter(instance_dir: &Path, path: &str, config: &str) -> Result<()> {
+    let starter_dir = instance_dir.join(path);
+    fs::create_dir_all(&starter_dir)?;
+    fs::write(starter_dir.join("jump-start.yaml"), config)?;
+    Ok(())
+}
+
+fn config(root: &Path) -> Config {
+    Config {
+        instances: vec![
+            JumpStartInstance {
+                name: "personal".to_string(),
+                path: root.join("personal"),
+                default: None,
+            },
+            JumpStartInstance {
+                name: "work".to_string(),
+                path: root.join("work"),
+                default: Some(true),
+            },
+        ],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_list_starters() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let root = temp_dir.path();
+    write_starter(
+        &root.join("personal"),
+        "react/app",
+        "description: A React app\nmainFile: src/App.jsx\n",
+    )?;
+    write_starter(&root.join("personal"), "react/hooks/fetch", "")?;
+    write_starter(&root.join("personal"), "python/cli", "description: A CLI\n")?;
+    write_starter(
+        &root.join("work"),
+        "react/dashboard",
+        "description: Charts\n",
+    )?;
+    let config = config(root);
+
+    let starters = list_starters(&config, None, None)?;
+    let identifiers: Vec<&str> = starters.iter().map(|s| s.identifier.as_str()).collect();
+    // Starters outside the default instance are prefixed with theirs
+    assert_eq!(
+        identifiers,
+        vec![
+            "personal/python/cli",
+            "personal/react/app",
+            "personal/react/hooks/fetch",
+            "react/dashboard",
+        ]
+    );
+    let app = &starters[1];
+    assert_eq!(app.instance, "personal");
+    assert_eq!(app.group, "react");
+    assert_eq!(app.name, "app");
+    assert_eq!(app.description.as_deref(), Some("A React app"));
+    assert_eq!(app.main_file.as_deref(), Some("src/App.jsx"));
+    assert_eq!(app.dir, root.join("personal/react/app"));
+
+    let starters = list_starters(&config, Some("personal"), Some("react"))?;
+    let identifiers: Vec<&str> = starters.iter().map(|s| s.identifier.as_str()).collect();
+    assert_eq!(
+        identifiers,
+        vec!["personal/react/app", "personal/react/hooks/fetch"]
+    );
+
+    let starters = list_starters(&config, None, Some("react/hooks"))?;
+    assert_eq!(starters.len(), 1);
+    assert_eq!(starters[0].group, "react/hooks");
+
+    assert!(list_starters(&config, Some("missing"), None).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_format_starters() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let root = temp_dir.path();
+    write_starter(
+        &root.join("work"),
+        "react/dashboard",
+        "description: |\n  Charts, \"live\"\n  and tables\nmainFile: src/App.jsx\n",
+    )?;
+    write_starter(&root.join("work"), "react/empty", "")?;
+    fs::create_dir_all(root.join("personal"))?;
+    let starters = list_starters(&config(root), None, None)?;
+
+    let table = format_starters(&starters, ListFormat::Table)?;
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 3, "{}", table);
+    let header: Vec<&str> = lines[0]
+        .split("  ")
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .collect();
+    assert_eq!(
+        header,
+        vec![
+            "STARTER",
+            "INSTANCE",
+            "GROUP",
+            "NAME",
+            "DESCRIPTION",
+            "MAIN FILE"
+        ]
+    );
+    // Columns line up with the header
+    let column = |name: &str| lines[0].find(name).unwrap();
+    assert_eq!(&lines[1][column("GROUP")..column("NAME")], "react  ");
+    assert_eq!(
+        &lines[1][column("NAME")..column("DESCRIPTION")],
+        "dashboard  "
+    );
+    assert!(
+        lines[1].contains("Charts, \"live\"") && lines[1].ends_with("src/App.jsx"),
+        "{}",
+        table
+    );
+    assert!(!table.contains("and tables"), "{}", table);
+
+    assert_eq!(
+        format_starters(&starters, ListFormat::Plain)?,
+        "react/dashboard\nreact/empty"
+    );
+
+    let json: serde_json::Value =
+        serde_json::from_str(&format_starters(&starters, ListFormat::Json)?)?;
+    assert_eq!(json[0]["main_file"], "src/App.jsx");
+    assert_eq!(json[1]["description"], serde_json::Value::Null);
+
+    let ndjson = format_starters(&starters, ListFormat::Ndjson)?;
+    let lines: Vec<serde_json::Value> = ndjson
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[1]["identifier"], "react/empty");
+
+    let csv = format_starters(&starters, ListFormat::Csv)?;
+    assert!(
+        csv.contains(",\"Charts, \"\"live\"\"\nand tables\n\",src/App.jsx,"),
+        "{}",
+        csv
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_list_json_output_has_no_log_messages() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("personal"))?;
+    write_starter(&root.join("work"), "react/app", "description: An app\n")?;
+    // A starter inside another starter and an invalid jump-start.yaml are both logged
+    write_starter(
+        &root.join("work"),
+        "react/app/inner",
+        "description: Inner\n",
+    )?;
+    write_starter(
+        &root.join("work"),
+        "react/broken",
+        "mainFile: [not, a, path]\n",
+    )?;
+
+    let output = run_jump_start(
+        &root.join("home"),
+        &config(root),
+        &["list", "--format", "json"],
+    )?;
+    assert!(output.status.success(), "{:?}", output);
+
+    let stdout = String::from_utf8(output.stdout)?;
+    let json: serde_json::Value = serde_json::from_str(&stdout)?;
+    let identifiers: Vec<&str> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["identifier"].as_str().unwrap())
+        .collect();
+    assert_eq!(identifiers, vec!["react/app", "react/app/inner"]);
+
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains("react/app/inner"), "{}", stderr);
+    assert!(stderr.contains("react/broken"), "{}", stderr);
+
+    Ok(())
+}
+
+#[test]
+fn test_list_unknown_keys_fail_only_in_strict_mode() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("personal"))?;
+    write_starter(&root.join("work"), "react/app", "mainfile: src/App.jsx\n")?;
+    let home = root.join("home");
+
+    // The typo is ignored when reading the starter, so it is still listed
+    let output = run_jump_start(&home, &config(root), &["list", "--format", "plain"])?;
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(String::from_utf8(output.stdout)?, "react/app\n");
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains("Unknown key \"mainfile\""), "{}", stderr);
+
+    let output = run_jump_start(&home, &config(root), &["list", "--strict"])?;
+    assert!(!output.status.success(), "{:?}", output);
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(
+        stderr.contains("react/app/jump-start.yaml:1:")
+            && stderr.contains("Unknown key \"mainfile\""),
+        "{}",
+        stderr
+    );
+
+    Ok(())
+}
@@ -3,9 +3,10 @@ use std::path::Path;
 
 use anyhow::Result;
 use handlebars::Handlebars;
-use jump_start::LocalStarter;
 use jump_start::commands::storybook::{generate_starter_story, generate_stories};
+use jump_start::commands::r#use::{self, UseOptions};
 use jump_start::starter::{StarterConfig, get_starter_files};
+use jump_start::{Config, JumpStartInstance, LocalStarter};
 use tempfile::tempdir;
 
 /// Test that we can generate a starter story MDX file
@@ -21,6 +22,7 @@ fn test_generate_starter_story() -> Result<()> {
             default_dir: None,
             main_file: None,
             preview: None,
+            ..Default::default()
         }),
     };
 
@@ -77,7 +79,7 @@ fn test_against_expected_stories() -> Result<()> {
     copy_dir_all(&starters_dir, &output_dir)?;
 
     // Generate storybook files
-    generate_stories(&output_dir)?;
+    generate_stories(&output_dir, false)?;
 
     // Get the story directories under the .storybook/stories directory
     let stories_dir = output_dir.join(".storybook/stories");
@@ -145,34 +147,34 @@ fn test_against_expected_stories() -> Result<()> {
 
                         // Verify key fields exist
                         if file_name == "starter.json" {
-                            if let Some(expected_desc) = expected_json.get("description") {
-                                if let Some(output_desc) = output_json.get("description") {
-                                    assert_eq!(
-                                        expected_desc, output_desc,
-                                        "Description mismatch for starter: {}/{}",
-                                        group_name, starter_name
-                                    );
-                                }
+                            if let Some(expected_desc) = expected_json.get("description")
+                                && let Some(output_desc) = output_json.get("description")
+                            {
+                                assert_eq!(
+                                    expected_desc, output_desc,
+                                    "Description mismatch for starter: {}/{}",
+                                    group_name, starter_name
+                                );
                             }
 
-                            if let Some(expected_group) = expected_json.get("group") {
-                                if let Some(output_group) = output_json.get("group") {
-                                    assert_eq!(
-                                        expected_group, output_group,
-                                        "Group mismatch for starter: {}/{}",
-                                        group_name, starter_name
-                                    );
-                                }
+                            if let Some(expected_group) = expected_json.get("group")
+                                && let Some(output_group) = output_json.get("group")
+                            {
+                                assert_eq!(
+                                    expected_group, output_group,
+                                    "Group mismatch for starter: {}/{}",
+                                    group_name, starter_name
+                                );
                             }
 
-                            if let Some(expected_name) = expected_json.get("title") {
-                                if let Some(output_name) = output_json.get("name") {
-                                    assert_eq!(
-                                        expected_name, output_name,
-                                        "Name mismatch for starter: {}/{}",
-                                        group_name, starter_name
-                                    );
-                                }
+                            if let Some(expected_name) = expected_json.get("title")
+                                && let Some(output_name) = output_json.get("name")
+                            {
+                                assert_eq!(
+                                    expected_name, output_name,
+                                    "Name mismatch for starter: {}/{}",
+                                    group_name, starter_name
+                                );
                             }
                         }
                     } else {
@@ -205,16 +207,16 @@ fn test_against_expected_stories() -> Result<()> {
                                 serde_json::from_str(&fs::read_to_string(&starter_json_path)?)?;
 
                             // Get the description from the starter.json
-                            if let Some(desc) = starter_json.get("description") {
-                                if let Some(desc_str) = desc.as_str() {
-                                    assert!(
-                                        output_content.contains(desc_str),
-                                        "Missing description in: {}/{}/{}",
-                                        group_name,
-                                        starter_name,
-                                        file_name
-                                    );
-                                }
+                            if let Some(desc) = starter_json.get("description")
+                                && let Some(desc_str) = desc.as_str()
+                            {
+                                assert!(
+                                    output_content.contains(desc_str),
+                                    "Missing description in: {}/{}/{}",
+                                    group_name,
+                                    starter_name,
+                                    file_name
+                                );
                             }
                         } else {
                             // For other files, compare the content directly
@@ -276,6 +278,7 @@ fn test_get_starter_files() -> Result<()> {
             default_dir: None,
             main_file: None,
             preview: None,
+            ..Default::default()
         }),
     };
 
@@ -325,6 +328,96 @@ fn test_get_starter_files() -> Result<()> {
     Ok(())
 }
 
+/// Files of optional features are marked in files.json
+#[test]
+fn test_get_starter_files_marks_optional_files() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let starter_dir = temp_dir.path().join("group/starter");
+    fs::create_dir_all(starter_dir.join("docker"))?;
+    fs::write(starter_dir.join("main.py"), "print('hi')")?;
+    fs::write(starter_dir.join("Dockerfile"), "FROM python")?;
+    fs::write(starter_dir.join("docker/compose.yml"), "services: {}")?;
+
+    let starter = LocalStarter {
+        group: "group".to_string(),
+        name: "starter".to_string(),
+        path: "group/starter".to_string(),
+        config: Some("features:\n  docker:\n    files: [Dockerfile, docker/]\n".parse()?),
+    };
+    let files = get_starter_files(&starter, temp_dir.path())?;
+    let features: std::collections::HashMap<_, _> = files
+        .iter()
+        .map(|f| (f.path.as_str(), f.features.clone()))
+        .collect();
+    assert_eq!(features["main.py"], Vec::<String>::new());
+    assert_eq!(features["Dockerfile"], vec!["docker"]);
+    assert_eq!(features["docker/compose.yml"], vec!["docker"]);
+
+    let json = serde_json::to_value(&files)?;
+    let main = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|f| f["path"] == "main.py")
+        .unwrap();
+    assert!(main.get("features").is_none());
+
+    Ok(())
+}
+
+/// The preview shows the same files `use` copies
+#[test]
+fn test_get_starter_files_applies_filter_like_use() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let instance_dir = temp_dir.path().join("instance");
+    let starter_dir = instance_dir.join("group/starter");
+    fs::create_dir_all(starter_dir.join("__pycache__"))?;
+    fs::create_dir_all(starter_dir.join("dist"))?;
+    let config = "exclude: [__pycache__/]\n";
+    fs::write(starter_dir.join("jump-start.yaml"), config)?;
+    fs::write(starter_dir.join(".jumpstartignore"), "dist/\n.DS_Store\n")?;
+    fs::write(starter_dir.join("main.py"), "print('hi')")?;
+    fs::write(starter_dir.join(".DS_Store"), "")?;
+    fs::write(starter_dir.join("__pycache__/main.pyc"), "")?;
+    fs::write(starter_dir.join("dist/main.js"), "")?;
+
+    let starter = LocalStarter {
+        group: "group".to_string(),
+        name: "starter".to_string(),
+        path: "group/starter".to_string(),
+        config: Some(config.parse()?),
+    };
+    let previewed: Vec<String> = get_starter_files(&starter, &instance_dir)?
+        .into_iter()
+        .map(|f| f.path)
+        .collect();
+    assert_eq!(previewed, vec!["main.py"]);
+
+    let dest_dir = temp_dir.path().join("dest");
+    r#use::r#use(
+        Config {
+            instances: vec![JumpStartInstance {
+                name: "instance".to_string(),
+                path: instance_dir.clone(),
+                default: Some(true),
+            }],
+            ..Default::default()
+        },
+        "group/starter",
+        Some(dest_dir.to_str().unwrap()),
+        &UseOptions {
+            no_manifest: true,
+            ..Default::default()
+        },
+    )?;
+    let copied: Vec<String> = fs::read_dir(&dest_dir)?
+        .map(|e| Ok(e?.file_name().to_string_lossy().to_string()))
+        .collect::<Result<_>>()?;
+    assert_eq!(copied, previewed);
+
+    Ok(())
+}
+
 // Helper function to recursively copy a directory
 fn copy_dir_all(src: &Path, dst: &Path) -> Result<()> {
     fs::create_dir_all(dst)?;
@@ -0,0 +1,129 @@
+use std::fs;
+
+use anyhow::Result;
+use jump_start::commands::upgrade::upgrade;
+use jump_start::commands::r#use::{self, UseOptions};
+use jump_start::manifest::{Manifest, hash_contents};
+use tempfile::tempdir;
+
+mod common;
+use common::{config, git};
+
+#[test]
+fn test_upgrade_local_starter() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let instance_dir = temp_dir.path().join("instance");
+    let starter_dir = instance_dir.join("group/starter");
+    let project_dir = temp_dir.path().join("project");
+
+    fs::create_dir_all(&starter_dir)?;
+    fs::write(starter_dir.join("jump-start.yaml"), "description: Test\n")?;
+    fs::write(
+        starter_dir.join("merged.txt"),
+        "one\ntwo\nthree\nfour\nfive\n",
+    )?;
+    fs::write(starter_dir.join("conflict.txt"), "version 1\n")?;
+    fs::write(starter_dir.join("updated.txt"), "old\n")?;
+    fs::write(starter_dir.join("deleted.txt"), "deleted\n")?;
+    fs::write(starter_dir.join("removed.txt"), "removed\n")?;
+    git(&["init", "-q"], &instance_dir)?;
+    git(&["add", "."], &instance_dir)?;
+    git(&["commit", "-q", "-m", "Add starter"], &instance_dir)?;
+
+    r#use::r#use(
+        config(&instance_dir),
+        "group/starter",
+        Some(project_dir.to_str().unwrap()),
+        &UseOptions {
+            ..Default::default()
+        },
+    )?;
+
+    // Edit the project
+    fs::write(
+        project_dir.join("merged.txt"),
+        "ONE\ntwo\nthree\nfour\nfive\n",
+    )?;
+    fs::write(project_dir.join("conflict.txt"), "my version\n")?;
+    fs::remove_file(project_dir.join("deleted.txt"))?;
+
+    // Change the starter
+    fs::write(
+        starter_dir.join("merged.txt"),
+        "one\ntwo\nthree\nfour\nFIVE\n",
+    )?;
+    fs::write(starter_dir.join("conflict.txt"), "version 2\n")?;
+    fs::write(starter_dir.join("updated.txt"), "new\n")?;
+    fs::write(starter_dir.join("deleted.txt"), "deleted, changed\n")?;
+    fs::remove_file(starter_dir.join("removed.txt"))?;
+    fs::write(starter_dir.join("added.txt"), "added\n")?;
+    git(&["add", "-A"], &instance_dir)?;
+    git(&["commit", "-q", "-m", "Update starter"], &instance_dir)?;
+
+    upgrade(
+        config(&instance_dir),
+        "group/starter",
+        Some(project_dir.to_str().unwrap()),
+        &UseOptions::default(),
+    )?;
+
+    let read = |name: &str| fs::read_to_string(project_dir.join(name));
+    assert_eq!(read("merged.txt")?, "ONE\ntwo\nthree\nfour\nFIVE\n");
+    assert_eq!(read("updated.txt")?, "new\n");
+    assert_eq!(read("added.txt")?, "added\n");
+    assert!(!project_dir.join("deleted.txt").exists());
+    assert!(!project_dir.join("removed.txt").exists());
+
+    let conflict = read("conflict.txt")?;
+    assert!(
+        conflict.contains("<<<<<<< ours\nmy version\n"),
+        "{}",
+        conflict
+    );
+    assert!(
+        conflict.contains("||||||| original\nversion 1\n"),
+        "{}",
+        conflict
+    );
+    assert!(conflict.contains("=======\nversion 2\n"), "{}", conflict);
+
+    // The manifest now records the upgraded starter, ready for the next upgrade
+    let manifest = Manifest::load(&project_dir)?.unwrap();
+    assert!(manifest.upgraded_at.is_some());
+    assert_eq!(manifest.files["updated.txt"], hash_contents(b"new\n"));
+    assert!(!manifest.files.contains_key("removed.txt"));
+    assert!(manifest.files.contains_key("deleted.txt"));
+
+    Ok(())
+}
+
+#[test]
+fn test_upgrade_requires_manifest() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let instance_dir = temp_dir.path().join("instance");
+    let project_dir = temp_dir.path().join("project");
+    fs::create_dir_all(instance_dir.join("group/starter"))?;
+    fs::write(instance_dir.join("group/starter/file.txt"), "v1\n")?;
+
+    r#use::r#use(
+        config(&instance_dir),
+        "group/starter",
+        Some(project_dir.to_str().unwrap()),
+        &UseOptions {
+            no_manifest: true,
+            ..Default::default()
+        },
+    )?;
+    assert!(Manifest::load(&project_dir)?.is_none());
+
+    let err = upgrade(
+        config(&instance_dir),
+        "group/starter",
+        Some(project_dir.to_str().unwrap()),
+        &UseOptions::default(),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("--no-manifest"), "{}", err);
+
+    Ok(())
+}
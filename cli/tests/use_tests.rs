@@ -1,11 +1,18 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
 
 use anyhow::Result;
-use jump_start::commands::r#use;
+use jump_start::commands::r#use::{self, CloneMode, UseOptions};
+use jump_start::copy::ConflictPolicy;
+use jump_start::host::{Host, HostKind};
+use jump_start::manifest::{Manifest, hash_contents};
 use jump_start::{Config, JumpStartInstance};
 use tempfile::tempdir;
 
+mod common;
+use common::{config, git, run_jump_start};
+
 fn setup_test_environment() -> Result<(PathBuf, PathBuf)> {
     let temp_dir = tempdir()?;
     let instance_dir = temp_dir.path().join("instance");
@@ -40,10 +47,16 @@ fn test_use_local_starter() -> Result<()> {
             path: instance_dir,
             default: Some(true),
         }],
+        ..Default::default()
     };
 
     // Call the use function with a local starter
-    r#use::r#use(config, "group/starter", Some(dest_dir.to_str().unwrap()))?;
+    r#use::r#use(
+        config,
+        "group/starter",
+        Some(dest_dir.to_str().unwrap()),
+        &UseOptions::default(),
+    )?;
 
     // Verify files were copied correctly
     assert!(dest_dir.join("file1.txt").exists());
@@ -56,6 +69,32 @@ fn test_use_local_starter() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_use_local_starter_in_nested_group() -> Result<()> {
+    let (temp_dir, instance_dir) = setup_test_environment()?;
+    let starter_dir = instance_dir.join("frontend/react/dashboard");
+    fs::create_dir_all(&starter_dir)?;
+    fs::write(starter_dir.join("jump-start.yaml"), "description: Nested\n")?;
+    fs::write(starter_dir.join("App.jsx"), "export default App;\n")?;
+    let dest_dir = temp_dir.join("dest");
+
+    let config = config(&instance_dir);
+
+    r#use::r#use(
+        config,
+        "test-instance/frontend/react/dashboard",
+        Some(dest_dir.to_str().unwrap()),
+        &UseOptions::default(),
+    )?;
+
+    assert_eq!(
+        fs::read_to_string(dest_dir.join("App.jsx"))?,
+        "export default App;\n"
+    );
+
+    Ok(())
+}
+
 #[test]
 fn test_use_local_starter_default_path() -> Result<()> {
     // Set up test environment
@@ -68,6 +107,7 @@ fn test_use_local_starter_default_path() -> Result<()> {
             path: instance_dir,
             default: Some(true),
         }],
+        ..Default::default()
     };
 
     // Set current directory to temp_dir for this test
@@ -75,7 +115,7 @@ fn test_use_local_starter_default_path() -> Result<()> {
     std::env::set_current_dir(&temp_dir)?;
 
     // Call the use function with default destination (from config)
-    r#use::r#use(config, "group/starter", None)?;
+    r#use::r#use(config, "group/starter", None, &UseOptions::default())?;
 
     // Test project directory should be created based on the config
     let project_dir = temp_dir.join("test-project");
@@ -87,3 +127,758 @@ fn test_use_local_starter_default_path() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_use_local_starter_from_named_instance() -> Result<()> {
+    let (temp_dir, instance_dir) = setup_test_environment()?;
+    let other_dir = temp_dir.join("other");
+    fs::create_dir_all(&other_dir)?;
+
+    // The starter lives in the non-default instance
+    let config = || Config {
+        instances: vec![
+            JumpStartInstance {
+                name: "other".to_string(),
+                path: other_dir.clone(),
+                default: Some(true),
+            },
+            JumpStartInstance {
+                name: "test-instance".to_string(),
+                path: instance_dir.clone(),
+                default: None,
+            },
+        ],
+        ..Default::default()
+    };
+
+    let err = r#use::r#use(
+        config(),
+        "group/starter",
+        Some(temp_dir.join("dest0").to_str().unwrap()),
+        &UseOptions::default(),
+    )
+    .unwrap_err();
+    assert!(format!("{:#}", err).contains("not found in instance \"other\""));
+
+    let dest_dir = temp_dir.join("dest1");
+    r#use::r#use(
+        config(),
+        "test-instance/group/starter",
+        Some(dest_dir.to_str().unwrap()),
+        &UseOptions::default(),
+    )?;
+    assert_eq!(
+        fs::read_to_string(dest_dir.join("file1.txt"))?,
+        "test content 1"
+    );
+
+    let dest_dir = temp_dir.join("dest2");
+    r#use::r#use(
+        config(),
+        "group/starter",
+        Some(dest_dir.to_str().unwrap()),
+        &UseOptions {
+            instance: Some("test-instance".to_string()),
+            ..Default::default()
+        },
+    )?;
+    assert!(dest_dir.join("nested/file2.txt").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_use_local_starter_dry_run() -> Result<()> {
+    let (temp_dir, instance_dir) = setup_test_environment()?;
+    let dest_dir = temp_dir.join("dest");
+    fs::create_dir_all(&dest_dir)?;
+    fs::write(dest_dir.join("file1.txt"), "my content")?;
+
+    let config = config(&instance_dir);
+
+    // A conflict doesn't abort a dry run, and nothing is written
+    r#use::r#use(
+        config,
+        "group/starter",
+        Some(dest_dir.to_str().unwrap()),
+        &UseOptions {
+            dry_run: true,
+            json: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        fs::read_to_string(dest_dir.join("file1.txt"))?,
+        "my content"
+    );
+    assert!(!dest_dir.join("nested").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_use_local_starter_with_variables() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let instance_dir = temp_dir.path().join("instance");
+    let starter_dir = instance_dir.join("python/script");
+    let dest_dir = temp_dir.path().join("dest");
+
+    fs::create_dir_all(&starter_dir)?;
+    fs::write(
+        starter_dir.join("jump-start.yaml"),
+        r#"
+description: A script
+variables:
+  - name: project_name
+    description: Name of the project
+  - name: year
+    type: number
+    default: 2025
+"#,
+    )?;
+    fs::write(
+        starter_dir.join("{{project_name}}.py"),
+        "# {{project_name}} (c) {{year}}\n",
+    )?;
+    fs::write(starter_dir.join("data.bin"), [0xff, 0xfe, b'{', b'{'])?;
+
+    let config = config(&instance_dir);
+    let options = UseOptions {
+        vars: vec![("project_name".to_string(), "convert".to_string())],
+        ..Default::default()
+    };
+
+    r#use::r#use(
+        config,
+        "python/script",
+        Some(dest_dir.to_str().unwrap()),
+        &options,
+    )?;
+
+    assert_eq!(
+        fs::read_to_string(dest_dir.join("convert.py"))?,
+        "# convert (c) 2025\n"
+    );
+    // Binary files are copied untouched
+    assert_eq!(
+        fs::read(dest_dir.join("data.bin"))?,
+        [0xff, 0xfe, b'{', b'{']
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_use_local_starter_with_degit_actions() -> Result<()> {
+    let (temp_dir, instance_dir) = setup_test_environment()?;
+    let starter_dir = instance_dir.join("group/starter");
+    let base_dir = instance_dir.join("group/base");
+    fs::create_dir_all(&base_dir)?;
+    fs::write(base_dir.join("LICENSE"), "MIT")?;
+    fs::write(base_dir.join("file1.txt"), "base content")?;
+    fs::write(base_dir.join("base.txt"), "from base")?;
+    fs::write(
+        starter_dir.join("degit.json"),
+        r#"[
+            {"action": "clone", "src": "group/base"},
+            {"action": "remove", "files": ["LICENSE", "nested"]}
+        ]"#,
+    )?;
+
+    let dest_dir = temp_dir.join("dest");
+    r#use::r#use(
+        config(&instance_dir),
+        "group/starter",
+        Some(dest_dir.to_str().unwrap()),
+        &UseOptions::default(),
+    )?;
+
+    // Cloned files replace the starter's own, then removals apply to both
+    assert_eq!(
+        fs::read_to_string(dest_dir.join("file1.txt"))?,
+        "base content"
+    );
+    assert_eq!(fs::read_to_string(dest_dir.join("base.txt"))?, "from base");
+    assert!(!dest_dir.join("LICENSE").exists());
+    assert!(!dest_dir.join("nested").exists());
+    assert!(!dest_dir.join("degit.json").exists());
+
+    fs::write(
+        starter_dir.join("degit.json"),
+        r#"[{"action": "rename", "from": "a", "to": "b"}]"#,
+    )?;
+    let err = r#use::r#use(
+        config(&instance_dir),
+        "group/starter",
+        Some(temp_dir.join("dest2").to_str().unwrap()),
+        &UseOptions::default(),
+    )
+    .unwrap_err();
+    let message = format!("{:#}", err);
+    assert!(message.contains("unknown variant `rename`"), "{}", message);
+    assert!(!temp_dir.join("dest2").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_use_local_starter_with_features() -> Result<()> {
+    let (temp_dir, instance_dir) = setup_test_environment()?;
+    let starter_dir = instance_dir.join("group/starter");
+    fs::write(
+        starter_dir.join("jump-start.yaml"),
+        r#"
+features:
+  docker:
+    description: Docker setup
+    files: [Dockerfile, docker/]
+  tests:
+    files: ["nested/*"]
+"#,
+    )?;
+    fs::create_dir_all(starter_dir.join("docker"))?;
+    fs::write(starter_dir.join("Dockerfile"), "FROM python")?;
+    fs::write(starter_dir.join("docker/entrypoint.sh"), "#!/bin/sh")?;
+
+    let dest_dir = temp_dir.join("dest");
+    r#use::r#use(
+        config(&instance_dir),
+        "group/starter",
+        Some(dest_dir.to_str().unwrap()),
+        &UseOptions {
+            with: vec!["docker".to_string()],
+            ..Default::default()
+        },
+    )?;
+    assert!(dest_dir.join("file1.txt").exists());
+    assert!(dest_dir.join("Dockerfile").exists());
+    assert!(dest_dir.join("docker/entrypoint.sh").exists());
+    assert!(!dest_dir.join("nested").exists());
+    assert_eq!(
+        Manifest::load(&dest_dir)?.unwrap().features,
+        vec!["docker".to_string()]
+    );
+
+    let dest_dir = temp_dir.join("dest2");
+    r#use::r#use(
+        config(&instance_dir),
+        "group/starter",
+        Some(dest_dir.to_str().unwrap()),
+        &UseOptions::default(),
+    )?;
+    assert!(dest_dir.join("file1.txt").exists());
+    assert!(!dest_dir.join("Dockerfile").exists());
+    assert!(!dest_dir.join("docker").exists());
+
+    let err = r#use::r#use(
+        config(&instance_dir),
+        "group/starter",
+        Some(temp_dir.join("dest3").to_str().unwrap()),
+        &UseOptions {
+            with: vec!["kubernetes".to_string()],
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    let message = format!("{:#}", err);
+    assert!(
+        message.contains("does not declare feature \"kubernetes\" (declared: docker, tests)"),
+        "{}",
+        message
+    );
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_use_local_starter_runs_post_use_hooks() -> Result<()> {
+    let (temp_dir, instance_dir) = setup_test_environment()?;
+    fs::write(
+        instance_dir.join("group/starter/jump-start.yaml"),
+        r#"
+hooks:
+  post_use:
+    - echo "$JUMP_START_STARTER_NAME" > hook.txt
+    - exit 1
+    - touch never.txt
+"#,
+    )?;
+
+    let dest_dir = temp_dir.join("dest");
+    let err = r#use::r#use(
+        config(&instance_dir),
+        "group/starter",
+        Some(dest_dir.to_str().unwrap()),
+        &UseOptions::default(),
+    )
+    .unwrap_err();
+    let message = format!("{:#}", err);
+    assert!(
+        message.contains("post_use hook 2/3 `exit 1` failed"),
+        "{}",
+        message
+    );
+    // The files were copied and earlier hooks ran before the failure
+    assert!(dest_dir.join("file1.txt").exists());
+    assert_eq!(fs::read_to_string(dest_dir.join("hook.txt"))?, "starter\n");
+    assert!(!dest_dir.join("never.txt").exists());
+
+    let dest_dir = temp_dir.join("dest2");
+    r#use::r#use(
+        config(&instance_dir),
+        "group/starter",
+        Some(dest_dir.to_str().unwrap()),
+        &UseOptions {
+            no_hooks: true,
+            ..Default::default()
+        },
+    )?;
+    assert!(dest_dir.join("file1.txt").exists());
+    assert!(!dest_dir.join("hook.txt").exists());
+
+    Ok(())
+}
+
+/// Create a bare repository at `<root>/remote/testuser/starters.git` holding two starters.
+///
+/// The first commit is tagged "v1" and has an older version of `group/starter/file1.txt`.
+/// Returns the SHA of that commit.
+fn setup_bare_repo(root: &Path) -> Result<String> {
+    let work_dir = root.join("work");
+    fs::create_dir_all(work_dir.join("group/starter/nested"))?;
+    fs::create_dir_all(work_dir.join("group/other"))?;
+    fs::write(work_dir.join("group/starter/file1.txt"), "old content 1")?;
+    fs::write(
+        work_dir.join("group/starter/nested/file2.txt"),
+        "test content 2",
+    )?;
+    fs::write(
+        work_dir.join("group/starter/jump-start.yaml"),
+        "defaultDir: ./test-project\n",
+    )?;
+    fs::write(work_dir.join("group/other/other.txt"), "other starter")?;
+
+    git(&["init", "-q"], &work_dir)?;
+    git(&["add", "."], &work_dir)?;
+    git(&["commit", "-q", "-m", "Add starters"], &work_dir)?;
+    git(&["tag", "v1"], &work_dir)?;
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(&work_dir)
+        .output()?;
+    let v1_sha = String::from_utf8(output.stdout)?.trim().to_string();
+
+    fs::write(work_dir.join("group/starter/file1.txt"), "test content 1")?;
+    git(&["commit", "-q", "-am", "Update starter"], &work_dir)?;
+
+    let remote_dir = root.join("remote/testuser");
+    fs::create_dir_all(&remote_dir)?;
+    git(
+        &[
+            "clone",
+            "-q",
+            "--bare",
+            work_dir.to_str().unwrap(),
+            "starters.git",
+        ],
+        &remote_dir,
+    )?;
+    Ok(v1_sha)
+}
+
+#[test]
+fn test_use_remote_starter_git_mode() -> Result<()> {
+    let temp_dir = tempdir()?;
+    setup_bare_repo(temp_dir.path())?;
+    let dest_dir = temp_dir.path().join("dest");
+
+    let config = Config {
+        cache_dir: Some(temp_dir.path().join("cache")),
+        git_base_url: Some(format!("file://{}/remote/", temp_dir.path().display())),
+        trusted_sources: vec!["testuser".to_string()],
+        ..Default::default()
+    };
+    let options = UseOptions {
+        mode: CloneMode::Git,
+        ..Default::default()
+    };
+
+    r#use::r#use(
+        config,
+        "@testuser/starters/group/starter",
+        Some(dest_dir.to_str().unwrap()),
+        &options,
+    )?;
+
+    assert_eq!(
+        fs::read_to_string(dest_dir.join("file1.txt"))?,
+        "test content 1"
+    );
+    assert_eq!(
+        fs::read_to_string(dest_dir.join("nested/file2.txt"))?,
+        "test content 2"
+    );
+    assert!(!dest_dir.join("jump-start.yaml").exists());
+    assert!(!dest_dir.join("other.txt").exists());
+
+    // The clone is cached and reused on the next run
+    let clone_dir = temp_dir
+        .path()
+        .join("cache/github/testuser/starters/.git-clone");
+    assert!(clone_dir.join(".git").exists());
+
+    let config = Config {
+        cache_dir: Some(temp_dir.path().join("cache")),
+        git_base_url: Some(format!("file://{}/remote/", temp_dir.path().display())),
+        trusted_sources: vec!["testuser".to_string()],
+        ..Default::default()
+    };
+    let second_dest = temp_dir.path().join("dest2");
+    r#use::r#use(
+        config,
+        "@testuser/starters/group/other",
+        Some(second_dest.to_str().unwrap()),
+        &options,
+    )?;
+    assert_eq!(
+        fs::read_to_string(second_dest.join("other.txt"))?,
+        "other starter"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_use_remote_starter_git_mode_missing_starter() -> Result<()> {
+    let temp_dir = tempdir()?;
+    setup_bare_repo(temp_dir.path())?;
+
+    let config = Config {
+        cache_dir: Some(temp_dir.path().join("cache")),
+        git_base_url: Some(format!("file://{}/remote/", temp_dir.path().display())),
+        trusted_sources: vec!["testuser".to_string()],
+        ..Default::default()
+    };
+    let options = UseOptions {
+        mode: CloneMode::Git,
+        ..Default::default()
+    };
+
+    let result = r#use::r#use(
+        config,
+        "@testuser/starters/group/missing",
+        Some(temp_dir.path().join("dest").to_str().unwrap()),
+        &options,
+    );
+    let err = format!("{:#}", result.unwrap_err());
+    assert!(err.contains("not found"), "unexpected error: {}", err);
+
+    Ok(())
+}
+
+#[test]
+fn test_use_remote_starter_with_trust_flag() -> Result<()> {
+    let temp_dir = tempdir()?;
+    setup_bare_repo(temp_dir.path())?;
+
+    // Only another repository of the same owner is trusted
+    let config = Config {
+        cache_dir: Some(temp_dir.path().join("cache")),
+        git_base_url: Some(format!("file://{}/remote/", temp_dir.path().display())),
+        trusted_sources: vec!["testuser/other-repo".to_string()],
+        ..Default::default()
+    };
+    let dest_dir = temp_dir.path().join("dest");
+    r#use::r#use(
+        config,
+        "@testuser/starters/group/starter",
+        Some(dest_dir.to_str().unwrap()),
+        &UseOptions {
+            mode: CloneMode::Git,
+            trust: true,
+            ..Default::default()
+        },
+    )?;
+    assert!(dest_dir.join("file1.txt").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_use_remote_starter_invalid_identifier() -> Result<()> {
+    let temp_dir = tempdir()?;
+
+    for identifier in [
+        "@testuser/starters/group/starter#xyz",
+        "@testuser/starters/group/starter@",
+        "@testuser/starters/group/starter@--upload-pack=touch pwned",
+        "@testuser/starter",
+    ] {
+        let result = r#use::r#use(
+            Config::default(),
+            identifier,
+            Some(temp_dir.path().join("dest").to_str().unwrap()),
+            &UseOptions::default(),
+        );
+        let err = format!("{:#}", result.unwrap_err());
+        assert!(
+            err.contains("Invalid starter identifier"),
+            "unexpected error for {}: {}",
+            identifier,
+            err
+        );
+    }
+    assert!(!temp_dir.path().join("dest").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_use_remote_starter_git_mode_pinned() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let v1_sha = setup_bare_repo(temp_dir.path())?;
+    let options = UseOptions {
+        mode: CloneMode::Git,
+        ..Default::default()
+    };
+    let config = || Config {
+        cache_dir: Some(temp_dir.path().join("cache")),
+        git_base_url: Some(format!("file://{}/remote/", temp_dir.path().display())),
+        trusted_sources: vec!["testuser".to_string()],
+        ..Default::default()
+    };
+
+    for (identifier, dir_name) in [
+        ("@testuser/starters/group/starter@v1", "starters@v1"),
+        (
+            &format!("@testuser/starters/group/starter#{}", v1_sha),
+            &format!("starters@{}", v1_sha),
+        ),
+        (
+            &format!("@testuser/starters/group/starter#{}", &v1_sha[..8]),
+            &format!("starters@{}", &v1_sha[..8]),
+        ),
+    ] {
+        let dest_dir = temp_dir.path().join("dest").join(dir_name);
+        r#use::r#use(
+            config(),
+            identifier,
+            Some(dest_dir.to_str().unwrap()),
+            &options,
+        )?;
+
+        assert_eq!(
+            fs::read_to_string(dest_dir.join("file1.txt"))?,
+            "old content 1",
+            "wrong contents for {}",
+            identifier
+        );
+        // Each ref is cached separately
+        assert!(
+            temp_dir
+                .path()
+                .join("cache/github/testuser")
+                .join(dir_name)
+                .join(".git-clone")
+                .exists()
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_use_writes_manifest() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let v1_sha = setup_bare_repo(temp_dir.path())?;
+    let dest_dir = temp_dir.path().join("dest");
+
+    let config = Config {
+        cache_dir: Some(temp_dir.path().join("cache")),
+        git_base_url: Some(format!("file://{}/remote/", temp_dir.path().display())),
+        trusted_sources: vec!["testuser".to_string()],
+        ..Default::default()
+    };
+    let options = UseOptions {
+        mode: CloneMode::Git,
+        ..Default::default()
+    };
+
+    r#use::r#use(
+        config,
+        "@testuser/starters/group/starter@v1",
+        Some(dest_dir.to_str().unwrap()),
+        &options,
+    )?;
+
+    let manifest = Manifest::load(&dest_dir)?.expect("manifest is written");
+    assert_eq!(manifest.starter, "@testuser/starters/group/starter@v1");
+    assert_eq!(
+        manifest.repository.as_deref(),
+        Some("github/testuser/starters")
+    );
+    assert_eq!(manifest.git_ref.as_deref(), Some("v1"));
+    assert_eq!(manifest.commit, Some(v1_sha));
+    assert_eq!(manifest.instance, None);
+    assert!(manifest.created_at > 0);
+    assert_eq!(
+        manifest.files.keys().collect::<Vec<_>>(),
+        vec!["file1.txt", "nested/file2.txt"]
+    );
+    assert_eq!(manifest.files["file1.txt"], hash_contents(b"old content 1"));
+
+    Ok(())
+}
+
+#[test]
+fn test_use_twice_into_same_dir() -> Result<()> {
+    let (temp_dir, instance_dir) = setup_test_environment()?;
+    let other_dir = instance_dir.join("group/other");
+    fs::create_dir_all(&other_dir)?;
+    fs::write(other_dir.join("other.txt"), "other content")?;
+    let dest_dir = temp_dir.join("dest");
+    let use_into_dest = |starter: &str, on_conflict| {
+        r#use::r#use(
+            config(&instance_dir),
+            starter,
+            Some(dest_dir.to_str().unwrap()),
+            &UseOptions {
+                on_conflict,
+                ..Default::default()
+            },
+        )
+    };
+
+    use_into_dest("group/starter", ConflictPolicy::Abort)?;
+    let manifest = Manifest::load(&dest_dir)?.unwrap();
+
+    // Using the same starter again leaves the unchanged manifest alone
+    use_into_dest("group/starter", ConflictPolicy::Abort)?;
+    assert_eq!(Manifest::load(&dest_dir)?.unwrap(), manifest);
+
+    // A skipped file keeps the hash recorded when it was first written
+    fs::write(dest_dir.join("file1.txt"), "my content")?;
+    use_into_dest("group/starter", ConflictPolicy::Skip)?;
+    assert_eq!(Manifest::load(&dest_dir)?.unwrap(), manifest);
+
+    // Another starter's manifest conflicts with the existing one, and nothing is written
+    let err = use_into_dest("group/other", ConflictPolicy::Abort).unwrap_err();
+    assert!(
+        format!("{:#}", err).contains(".jump-start.json"),
+        "{:#}",
+        err
+    );
+    assert!(!dest_dir.join("other.txt").exists());
+    assert_eq!(Manifest::load(&dest_dir)?.unwrap(), manifest);
+
+    // Skipping the manifest keeps the first starter's
+    use_into_dest("group/other", ConflictPolicy::Skip)?;
+    assert!(dest_dir.join("other.txt").exists());
+    assert_eq!(Manifest::load(&dest_dir)?.unwrap(), manifest);
+
+    use_into_dest("group/other", ConflictPolicy::Overwrite)?;
+    let manifest = Manifest::load(&dest_dir)?.unwrap();
+    assert_eq!(manifest.starter, "group/other");
+    assert_eq!(manifest.files.keys().collect::<Vec<_>>(), vec!["other.txt"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_use_remote_starter_dry_run_json_output() -> Result<()> {
+    let temp_dir = tempdir()?;
+
+    // A GitHub-style archive of testuser/starters at HEAD
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+        Vec::new(),
+        flate2::Compression::default(),
+    ));
+    for (path, contents) in [
+        ("starters-main/group/starter/file1.txt", "test content 1"),
+        (
+            "starters-main/group/starter/jump-start.yaml",
+            "description: A starter\n",
+        ),
+    ] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, path, contents.as_bytes())?;
+    }
+    let archive = builder.into_inner()?.finish()?;
+
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/testuser/starters/archive/HEAD.tar.gz")
+        .with_status(200)
+        .with_body(archive)
+        .create();
+    let config = Config {
+        instances: vec![JumpStartInstance {
+            name: "test-instance".to_string(),
+            path: temp_dir.path().join("instance"),
+            default: Some(true),
+        }],
+        cache_dir: Some(temp_dir.path().join("cache")),
+        hosts: vec![Host {
+            name: "test".to_string(),
+            kind: HostKind::Github,
+            url: server.url(),
+            git_url: None,
+            default: None,
+            token: None,
+            token_env: None,
+        }],
+        trusted_sources: vec!["test:testuser".to_string()],
+        // Check the remote on every run
+        cache_ttl: Some(0),
+        ..Default::default()
+    };
+    let dest_dir = temp_dir.path().join("dest");
+    let use_dry_run = || {
+        run_jump_start(
+            &temp_dir.path().join("home"),
+            &config,
+            &[
+                "use",
+                "test:@testuser/starters/group/starter",
+                dest_dir.to_str().unwrap(),
+                "--dry-run",
+                "--json",
+            ],
+        )
+    };
+    let planned_files = |output: &Output| -> Result<Vec<serde_json::Value>> {
+        assert!(output.status.success(), "{:?}", output);
+        let planned: Vec<serde_json::Value> = String::from_utf8(output.stdout.clone())?
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?;
+        Ok(planned)
+    };
+
+    let output = use_dry_run()?;
+    mock.assert();
+    let planned = planned_files(&output)?;
+    assert_eq!(planned.len(), 2, "{:?}", planned);
+    assert_eq!(planned[0]["path"], "file1.txt");
+    assert_eq!(planned[0]["action"], "create");
+    assert_eq!(planned[1]["path"], ".jump-start.json");
+    assert!(!dest_dir.exists());
+
+    // When the remote fails, the warnings about using the cached copy go to stderr
+    mock.remove();
+    let mock = server
+        .mock("GET", "/testuser/starters/archive/HEAD.tar.gz")
+        .with_status(500)
+        .create();
+    let output = use_dry_run()?;
+    mock.assert();
+    assert_eq!(planned_files(&output)?, planned);
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains("Using cached copy"), "{}", stderr);
+
+    Ok(())
+}
@@ -0,0 +1,162 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use jump_start::commands::validate::{Severity, validate, validate_instance};
+use jump_start::{Config, JumpStartInstance};
+use tempfile::tempdir;
+
+fn write_starter(instance_dir: &Path, path: &str, config: &str, files: &[&str]) -> Result<()> {
+    let starter_dir = instance_dir.join(path);
+    fs::create_dir_all(&starter_dir)?;
+    fs::write(starter_dir.join("jump-start.yaml"), config)?;
+    for file in files {
+        fs::write(starter_dir.join(file), "contents\n")?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_validate_instance() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let instance_dir = temp_dir.path();
+    write_starter(
+        instance_dir,
+        "group/good",
+        "description: Fine\nmainFile: index.js\ndefaultDir: ./app\n",
+        &["index.js"],
+    )?;
+    write_starter(
+        instance_dir,
+        "group/bad",
+        "mainFile: missing.js\ndefaultDir: ../outside\nnmae: typo\nhooks:\n  postUse: [ls]\n",
+        &["index.js"],
+    )?;
+    write_starter(instance_dir, "group/empty", "description: Empty\n", &[])?;
+    write_starter(
+        instance_dir,
+        "group/broken",
+        "description: [unclosed\n",
+        &["index.js"],
+    )?;
+    write_starter(instance_dir, "ungrouped", "description: Ungrouped\n", &[])?;
+
+    let issues = validate_instance(instance_dir)?;
+    let found: Vec<(Severity, Option<&str>, &str)> = issues
+        .iter()
+        .map(|i| (i.severity, i.starter.as_deref(), i.message.as_str()))
+        .collect();
+
+    assert!(
+        !found.iter().any(|(_, s, _)| *s == Some("group/good")),
+        "{:?}",
+        found
+    );
+    for (severity, starter, message) in [
+        (Severity::Error, Some("group/bad"), "Unknown key \"nmae\""),
+        (
+            Severity::Error,
+            Some("group/bad"),
+            "Unknown key \"postUse\" in /hooks",
+        ),
+        (Severity::Warning, Some("group/bad"), "Missing description"),
+        (
+            Severity::Error,
+            Some("group/bad"),
+            "defaultDir \"../outside\"",
+        ),
+        (
+            Severity::Error,
+            Some("group/bad"),
+            "mainFile \"missing.js\"",
+        ),
+        (Severity::Error, Some("group/empty"), "No files to copy"),
+        (
+            Severity::Error,
+            Some("group/broken"),
+            "Invalid jump-start.yaml",
+        ),
+        (Severity::Error, None, "inside a group directory"),
+    ] {
+        assert!(
+            found
+                .iter()
+                .any(|(s, st, m)| *s == severity && *st == starter && m.contains(message)),
+            "Missing {:?} {:?} {:?} in {:?}",
+            severity,
+            starter,
+            message,
+            found
+        );
+    }
+    assert_eq!(found.len(), 8, "{:?}", found);
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_fails_on_errors() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let instance_dir = temp_dir.path().join("instance");
+    write_starter(
+        &instance_dir,
+        "group/app",
+        "mainFile: index.js\n",
+        &["index.js"],
+    )?;
+    let config = |strict| Config {
+        instances: vec![JumpStartInstance {
+            name: "test-instance".to_string(),
+            path: instance_dir.clone(),
+            default: Some(true),
+        }],
+        strict,
+        ..Default::default()
+    };
+
+    // A missing description is only a warning
+    validate(config(false), None, true)?;
+    assert!(validate(config(true), None, true).is_err());
+
+    fs::remove_file(instance_dir.join("group/app/index.js"))?;
+    let err = validate(config(false), None, false).unwrap_err();
+    assert!(err.to_string().contains("2 errors"), "{}", err);
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_instance_keeps_going_past_unreadable_files() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let instance_dir = temp_dir.path();
+    write_starter(instance_dir, "group/binary", "", &["index.js"])?;
+    fs::write(
+        instance_dir.join("group/binary/jump-start.yaml"),
+        [0xff, 0xfe],
+    )?;
+    write_starter(instance_dir, "group/later", "", &["index.js"])?;
+
+    // The unreadable config is reported, and starters after it are still checked
+    let issues = validate_instance(instance_dir)?;
+    let found: Vec<(Severity, Option<&str>, &str)> = issues
+        .iter()
+        .map(|i| (i.severity, i.starter.as_deref(), i.message.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (
+                Severity::Error,
+                Some("group/binary"),
+                "Couldn't read jump-start.yaml: stream did not contain valid UTF-8"
+            ),
+            (
+                Severity::Warning,
+                Some("group/later"),
+                "Missing description"
+            ),
+        ]
+    );
+
+    Ok(())
+}
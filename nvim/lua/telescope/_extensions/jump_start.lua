@@ -60,6 +60,37 @@ local function make_entry_from_jump_start(output)
 	}
 end
 
+local function make_entry_from_jump_start_list(output)
+	-- Parse one line of `jump-start list --format ndjson`
+	local ok, parsed = pcall(vim.json.decode, output)
+
+	if not ok then
+		debug_print("Failed to parse JSON output: " .. output)
+		return nil
+	end
+
+	local parts = {
+		starter_path = parsed.dir,
+		yaml_path = parsed.dir .. "/jump-start.yaml",
+		instance = parsed.instance,
+		group = parsed.group,
+		name = parsed.name,
+		main_file = parsed.main_file,
+	}
+
+	local display = generate_display(parts)
+	if parsed.description ~= vim.NIL and parsed.description then
+		display = display .. "  " .. vim.split(parsed.description, "\n")[1]
+	end
+
+	return {
+		value = parts,
+		display = display,
+		ordinal = display,
+		path = parts.yaml_path,
+	}
+end
+
 local function make_entry_from_fd(starter_entry, output)
 	local use_command = generate_use_command(starter_entry.value)
 
@@ -150,6 +181,35 @@ local function find(opts)
 			:find()
 end
 
+-- A Telescope picker listing every starter, filtered as you type
+-- Uses `jump-start list`. Selecting a starter opens a new
+-- Telescope picker (inspect_starter).
+--
+-- @argument opts - Telescope opts
+local function list(opts)
+	opts = opts or {}
+
+	pickers
+			.new(opts, {
+				prompt_title = "List jump start starters (Press <CR> to inspect one)",
+				finder = finders.new_oneshot_job(
+					{ "jump-start", "list", "--format", "ndjson" },
+					{ entry_maker = make_entry_from_jump_start_list }
+				),
+				sorter = conf.generic_sorter(opts),
+				previewer = conf.file_previewer(opts),
+				attach_mappings = function(prompt_bufnr, map)
+					actions.select_default:replace(function()
+						actions.close(prompt_bufnr)
+						local starter_entry = action_state.get_selected_entry()
+						inspect_starter(opts, starter_entry)
+					end)
+					return true
+				end,
+			})
+			:find()
+end
+
 local function setup(ext_config, config)
 	-- ext_config and config are optional
 end
@@ -158,5 +218,6 @@ return require("telescope").register_extension({
 	setup = setup,
 	exports = {
 		find = find,
+		list = list,
 	},
 })